@@ -0,0 +1,324 @@
+use ::nalgebra::Rotation3;
+use ::nalgebra::Unit;
+use ::nalgebra::Vector3;
+use ::raytracing::Float;
+use ::raytracing::scene::Aperture;
+use ::raytracing::scene::Camera;
+use ::winit::dpi::PhysicalPosition;
+use ::winit::event::ElementState;
+use ::winit::event::KeyboardInput;
+use ::winit::event::ModifiersState;
+use ::winit::event::MouseButton;
+use ::winit::event::MouseScrollDelta;
+use ::winit::event::VirtualKeyCode;
+use ::winit::event::WindowEvent;
+
+/// The speed at which the camera flies, in units per second.
+const SPEED: Float = 2.0;
+
+/// The factor by which holding shift speeds up flying and zooming.
+const FAST: Float = 4.0;
+
+/// The factor by which holding control slows down flying and zooming.
+const SLOW: Float = 0.25;
+
+/// The angle by which the camera turns per pixel the mouse is dragged, in
+/// radians.
+const SENSITIVITY: Float = 0.005;
+
+/// The distance the camera moves per line scrolled while flying.
+const ZOOM_STEP: Float = 0.5;
+
+/// The factor by which the distance to the target shrinks per line scrolled
+/// while orbiting.
+const ORBIT_ZOOM: Float = 0.9;
+
+/// The number of pixels scrolled by a touchpad which count as a line.
+const PIXELS_PER_LINE: Float = 20.0;
+
+/// The largest cosine of the angle between the camera's forward direction
+/// and the vertical, which keeps mouse-look from flipping the camera over.
+const MAX_PITCH_COS: Float = 0.99;
+
+/// How the camera moves in response to the mouse.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+
+    /// Dragging the mouse turns the camera in place.
+    Fly,
+
+    /// Dragging the mouse moves the camera around a target, which it keeps
+    /// looking at.
+    Orbit
+
+}
+
+/// Navigation of a camera with the keyboard and mouse.
+///
+/// W, A, S and D fly forwards, leftwards, backwards and rightwards, and E and
+/// Q fly up and down, faster with shift held and slower with control held.
+/// Dragging with the left mouse button looks around, scrolling zooms, Tab
+/// switches between flying and orbiting, R resets the view and P prints the
+/// camera.
+pub struct Controls {
+
+    /// The camera to return to when the view is reset.
+    initial: Camera,
+
+    /// How the camera moves in response to the mouse.
+    mode: Mode,
+
+    /// The point around which the camera orbits.
+    target: Vector3<Float>,
+
+    /// The keys which are held down.
+    held: Vec<VirtualKeyCode>,
+
+    /// The modifier keys which are held down.
+    modifiers: ModifiersState,
+
+    /// Whether the left mouse button is held down.
+    dragging: bool,
+
+    /// The last known position of the cursor, if it is in the window.
+    cursor: Option<PhysicalPosition<f64>>
+
+}
+
+impl Controls {
+
+    /// Creates controls which start flying from, and reset to, `camera`.
+    pub fn new(camera: &Camera) -> Self {
+        Controls {
+            initial: camera.clone(),
+            mode: Mode::Fly,
+            target: Vector3::zeros(),
+            held: Vec::new(),
+            modifiers: ModifiersState::empty(),
+            dragging: false,
+            cursor: None
+        }
+    }
+
+    /// Returns how the camera moves in response to the mouse.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Updates `camera` and the state of these controls in response to
+    /// `event`, returning whether the view was reset to the initial camera.
+    pub fn handle_event(
+        &mut self,
+        event: &WindowEvent,
+        camera: &mut Camera
+    ) -> bool {
+        match *event {
+            WindowEvent::KeyboardInput {
+                input: KeyboardInput {
+                    state,
+                    virtual_keycode: Some(key),
+                    ..
+                },
+                ..
+            } => return self.handle_key(key, state, camera),
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+            },
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                self.dragging = state == ElementState::Pressed;
+            },
+            WindowEvent::CursorMoved { position, .. } => {
+                if let (true, Some(last)) = (self.dragging, self.cursor) {
+                    let dx = (position.x - last.x) as Float;
+                    let dy = (position.y - last.y) as Float;
+                    self.turn(dx, dy, camera);
+                }
+                self.cursor = Some(position);
+            },
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor = None;
+            },
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y as Float,
+                    MouseScrollDelta::PixelDelta(d) => {
+                        d.y as Float / PIXELS_PER_LINE
+                    }
+                };
+                self.zoom(lines, camera);
+            },
+            WindowEvent::Focused(false) => {
+                // keys released while unfocused are never reported
+                self.held.clear();
+                self.dragging = false;
+            },
+            _ => {}
+        }
+        false
+    }
+
+    /// Moves `camera` according to the keys held down over the `dt` seconds
+    /// since the last update.
+    pub fn update(&mut self, camera: &mut Camera, dt: Float) {
+        // the velocity forwards, leftwards and upwards
+        let velocity: Vector3<Float> = self.held.iter()
+            .filter_map(|key| match key {
+                VirtualKeyCode::W => Some(Vector3::x()),
+                VirtualKeyCode::S => Some(-Vector3::x()),
+                VirtualKeyCode::A => Some(Vector3::y()),
+                VirtualKeyCode::D => Some(-Vector3::y()),
+                VirtualKeyCode::E => Some(Vector3::z()),
+                VirtualKeyCode::Q => Some(-Vector3::z()),
+                _ => None
+            })
+            .sum();
+        if velocity != Vector3::zeros() {
+            let step = velocity * SPEED * self.speed_factor() * dt;
+            let pos = camera.pos;
+            camera.dolly(step.x);
+            camera.pan(step.y, step.z);
+            // orbiting moves the target along with the camera
+            self.target += camera.pos - pos;
+        }
+    }
+
+    /// Returns the factor by which the modifier keys held down speed up
+    /// movement.
+    fn speed_factor(&self) -> Float {
+        if self.modifiers.shift() {
+            FAST
+        } else if self.modifiers.ctrl() {
+            SLOW
+        } else {
+            1.0
+        }
+    }
+
+    /// Updates `camera` and the state of these controls in response to `key`
+    /// entering `state`, returning whether the view was reset.
+    fn handle_key(
+        &mut self,
+        key: VirtualKeyCode,
+        state: ElementState,
+        camera: &mut Camera
+    ) -> bool {
+        if state == ElementState::Released {
+            self.held.retain(|&k| k != key);
+            return false
+        }
+        if self.held.contains(&key) {
+            // ignore repeated presses from holding the key down
+            return false
+        }
+        self.held.push(key);
+        match key {
+            VirtualKeyCode::Tab => self.toggle_mode(camera),
+            VirtualKeyCode::R => {
+                *camera = self.initial.clone();
+                self.mode = Mode::Fly;
+                return true
+            },
+            VirtualKeyCode::P => println!("{}", camera_code(camera)),
+            _ => {}
+        }
+        false
+    }
+
+    /// Switches between flying and orbiting, choosing as the target the point
+    /// in front of `camera` closest to the origin.
+    fn toggle_mode(&mut self, camera: &Camera) {
+        self.mode = match self.mode {
+            Mode::Fly => {
+                let forward = camera.forward.normalize();
+                let dist = (-camera.pos).dot(&forward).max(1.0);
+                self.target = camera.pos + forward * dist;
+                Mode::Orbit
+            },
+            Mode::Orbit => Mode::Fly
+        };
+    }
+
+    /// Turns `camera` in response to the mouse being dragged by `dx` pixels
+    /// rightwards and `dy` pixels downwards.
+    fn turn(&self, dx: Float, dy: Float, camera: &mut Camera) {
+        let vertical = Vector3::y_axis();
+        let left = Unit::new_normalize(camera.left());
+        // dragging moves the scene along with the cursor while orbiting
+        let sign = match self.mode {
+            Mode::Fly => 1.0,
+            Mode::Orbit => -1.0
+        };
+        let angle = sign * SENSITIVITY;
+        let yaw = Rotation3::from_axis_angle(&vertical, angle * dx);
+        let pitch = Rotation3::from_axis_angle(&left, -angle * dy);
+        let rotation = yaw * pitch;
+        // refuse to pitch past the vertical
+        let pitched = (rotation * camera.forward).normalize();
+        let rotation = if pitched.dot(&vertical).abs() > MAX_PITCH_COS {
+            yaw
+        } else {
+            rotation
+        };
+        match self.mode {
+            Mode::Fly => camera.rotate(&rotation),
+            Mode::Orbit => camera.orbit(self.target, &rotation)
+        }
+    }
+
+    /// Moves `camera` towards what it looks at in response to scrolling by
+    /// `lines` lines.
+    fn zoom(&self, lines: Float, camera: &mut Camera) {
+        let lines = lines * self.speed_factor();
+        match self.mode {
+            Mode::Fly => camera.dolly(lines * ZOOM_STEP),
+            Mode::Orbit => {
+                let dist = (camera.pos - self.target).norm();
+                camera.dolly(dist * (1.0 - ORBIT_ZOOM.powf(lines)));
+            }
+        }
+    }
+
+}
+
+/// Returns the Rust code which constructs `camera`.
+fn camera_code(camera: &Camera) -> String {
+    let vector = |v: Vector3<Float>| format!(
+        "Vector3::new({:?}, {:?}, {:?})",
+        v.x,
+        v.y,
+        v.z
+    );
+    let lens = match camera.lens {
+        Some(lens) => format!(
+            "Some(Lens {{\n        radius: {:?},\n        \
+                focal_distance: {:?},\n        aperture: {}\n    }})",
+            lens.radius,
+            lens.focal_distance,
+            match lens.aperture {
+                Aperture::Circle => "Aperture::Circle".to_string(),
+                Aperture::Polygon { blades, rotation } => format!(
+                    "Aperture::Polygon {{ blades: {}, rotation: {:?} }}",
+                    blades,
+                    rotation
+                )
+            }
+        ),
+        None => "None".to_string()
+    };
+    format!(
+        "Camera {{\n    pos: {},\n    forward: {},\n    up: {},\n    \
+            projection: Arc::new({:?}),\n    lens: {},\n    \
+            shutter: Shutter {{ open: {:?}, close: {:?} }}\n}}",
+        vector(camera.pos),
+        vector(camera.forward),
+        vector(camera.up),
+        camera.projection,
+        lens,
+        camera.shutter.open,
+        camera.shutter.close
+    )
+}
@@ -1,36 +1,58 @@
 use ::anyhow::Result;
+use ::anyhow::anyhow;
 use ::nalgebra::Vector3;
 use ::pixels::Pixels;
 use ::pixels::SurfaceTexture;
-use ::rayon::iter::ParallelIterator;
-use ::rayon::slice::ParallelSliceMut;
 use ::raytracing::Color;
-use ::raytracing::rasterize_into;
-use ::raytracing::render::NaiveRenderer;
+use ::raytracing::Float;
+use ::raytracing::OutputTransform;
+use ::raytracing::consts::PI;
+use ::raytracing::render::RendererRegistry;
 use ::raytracing::scene::Camera;
+use ::raytracing::scene::Equirectangular;
+use ::raytracing::scene::Fisheye;
+use ::raytracing::scene::FisheyeMapping;
 use ::raytracing::scene::Light;
+use ::raytracing::scene::Orthographic;
+use ::raytracing::scene::Perspective;
+use ::raytracing::scene::Projection;
 use ::raytracing::scene::Scene;
+use ::raytracing::scene::Shutter;
+use ::raytracing::shape::Motion;
+use ::raytracing::shape::Moving;
 use ::raytracing::shape::Sphere;
-use ::std::f64::consts::PI;
+use ::std::sync::Arc;
 use ::time::Instant;
 use ::winit::dpi::PhysicalSize;
+use ::winit::event::ElementState;
 use ::winit::event::Event;
+use ::winit::event::KeyboardInput;
+use ::winit::event::VirtualKeyCode;
 use ::winit::event::WindowEvent;
 use ::winit::event_loop::ControlFlow;
 use ::winit::event_loop::EventLoop;
 use ::winit::window::WindowBuilder;
 
+mod controls;
+mod progressive;
+mod selection;
+
+use controls::Controls;
+use controls::Mode;
+use progressive::Progressive;
+use selection::Selection;
+
 const WIDTH: u32 = 800;
 const HEIGHT: u32 = 600;
-const FOV_HORIZ: f64 = 0.5 * PI;
-const FOV_VERT: f64 = 0.375 * PI;
+const SHUTTER_TIME: Float = 0.25;
+const PROJECTIONS: usize = 4;
 
 fn main() -> Result<()> {
     // the controller for new windows
     let event_loop = EventLoop::new();
 
     // create a new window and obtain a drawing surface for it
-    let mut size = PhysicalSize::new(WIDTH, HEIGHT);
+    let size = PhysicalSize::new(WIDTH, HEIGHT);
     let window =
         WindowBuilder::new()
         .with_inner_size(size)
@@ -46,97 +68,255 @@ fn main() -> Result<()> {
         Pixels::new(size.width, size.height, surface_texture)?
     };
 
-    // create a scene and a renderer for it
-    let mut scene = construct_scene();
+    // create a scene and choose a renderer for it
+    let mut scene = construct_scene()?;
+    let registry = RendererRegistry::default();
+    // the index in the registry of the renderer
+    let mut renderer_index = choose_renderer(&registry)?;
+    // keyboard and mouse navigation of the scene's camera
+    let mut controls = Controls::new(&scene.camera);
+    // mouse selection of the region of the window to render
+    let mut selection = Selection::default();
+    // the running average of the samples rendered since the scene last changed
+    let mut progressive = Progressive::new(size.width, size.height);
+    let transform = OutputTransform::default();
 
-    let start_time = Instant::now();
+    // the time of the scene, which stands still while paused
+    let mut t = 0.0;
+    let mut paused = false;
+    // whether moving spheres are blurred over the shutter interval
+    let mut motion_blur = false;
+    // the index of the projection of the camera
+    let mut projection = 0;
+    let mut last_frame = Instant::now();
 
     event_loop.run(move |event, _, ctrl_flow| {
         ctrl_flow.set_poll();
         match event {
             Event::MainEventsCleared => {
-                let t = (Instant::now() - start_time).as_seconds_f64();
+                let now = Instant::now();
+                let dt = (now - last_frame).as_seconds_f64() as Float;
+                last_frame = now;
+                if !paused {
+                    t += dt;
+                }
+                controls.update(&mut scene.camera, dt);
+                scene.camera.shutter = if motion_blur {
+                    Shutter { open: 0.0, close: SHUTTER_TIME }
+                } else {
+                    Shutter::instant(0.0)
+                };
+                // keep drawing the outline of a region being selected
+                let dragging = selection.dragging();
+                if progressive.is_converged(t, &scene.camera)
+                    && dragging.is_none() {
+                    return
+                }
                 // mutably borrow the scene and update its camera and lighting
                 update_scene(&mut scene, t);
                 // create a new renderer for the updated scene
-                let renderer = NaiveRenderer::new(&scene);
-                rasterize_into(
-                    &renderer,
-                    pixels
-                        .get_frame_mut()
-                        .par_chunks_exact_mut(4)
-                        // convert the [u8] slices into [u8; 4] arrays
-                        .map(|pixel| {
-                            TryInto::<&mut [u8; 4]>::try_into(pixel).unwrap()
-                        }),
-                    FOV_HORIZ,
-                    FOV_VERT,
-                    size.width,
-                    size.height
-                ).unwrap();
+                let name = registry.names().nth(renderer_index).unwrap();
+                let renderer = registry.create(name, &scene).unwrap();
+                let camera = &scene.camera;
+                if let Err(e) = progressive.render(&renderer, t, camera) {
+                    // keep the samples of the tiles rendered before the error
+                    eprintln!("failed to render a sample: {e}");
+                }
+                let image = progressive.image();
+                image.write_rgba8(&transform, pixels.get_frame_mut());
+                if let Some(region) = dragging {
+                    let frame = pixels.get_frame_mut();
+                    selection::draw_outline(&region, frame, image.width());
+                }
+                window.set_title(&format!(
+                    "example_rt ({}, {} samples, {}{}{}{})",
+                    name,
+                    progressive.samples(),
+                    match controls.mode() {
+                        Mode::Fly => "flying",
+                        Mode::Orbit => "orbiting"
+                    },
+                    if motion_blur { ", motion blur" } else { "" },
+                    match progressive.region() {
+                        Some(_) => ", region",
+                        None => ""
+                    },
+                    if paused { ", paused" } else { "" }
+                ));
                 window.request_redraw();
             },
             Event::RedrawRequested(_) => {
                 pixels.render().unwrap();
             },
-            Event::WindowEvent { event: WindowEvent::Resized(size2), .. } => {
-                size = size2;
+            Event::WindowEvent { event: WindowEvent::Resized(size), .. } => {
                 pixels.resize_buffer(size.width, size.height);
                 pixels.resize_surface(size.width, size.height);
+                progressive.resize(size.width, size.height);
+                selection = Selection::default();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput {
+                    input: KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::Space),
+                        ..
+                    },
+                    ..
+                },
+                ..
+            } => {
+                // freeze the scene so that samples accumulate
+                paused = !paused;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput {
+                    input: KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::B),
+                        ..
+                    },
+                    ..
+                },
+                ..
+            } => {
+                motion_blur = !motion_blur;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput {
+                    input: KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::V),
+                        ..
+                    },
+                    ..
+                },
+                ..
+            } => {
+                // cycle through the projections of the camera
+                projection = (projection + 1) % PROJECTIONS;
+                scene.camera.projection = projection_at(projection);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput {
+                    input: KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(VirtualKeyCode::G),
+                        ..
+                    },
+                    ..
+                },
+                ..
+            } => {
+                // cycle through the renderers of the scene
+                renderer_index = (renderer_index + 1) % registry.len();
+                progressive.reset();
             }
             Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
                 *ctrl_flow = ControlFlow::Exit
             },
+            Event::WindowEvent { event, .. } => {
+                if controls.handle_event(&event, &mut scene.camera) {
+                    // the initial camera has the first projection
+                    projection = 0;
+                }
+                if selection.handle_event(&event) {
+                    progressive.set_region(selection.selected());
+                }
+            },
             _ => {}
         }
     });
 }
 
-fn construct_scene() -> Scene<'static> {
-    Scene {
+/// Returns the projection at index `i` of those the camera cycles through.
+fn projection_at(i: usize) -> Arc<dyn Projection> {
+    match i % PROJECTIONS {
+        0 => Arc::new(Perspective {
+            fov_horiz: 0.5 * PI,
+            fov_vert: 0.375 * PI
+        }),
+        1 => Arc::new(Orthographic { height: 6.0 }),
+        2 => Arc::new(Fisheye {
+            fov: PI,
+            mapping: FisheyeMapping::Equidistant
+        }),
+        _ => Arc::new(Equirectangular { ipd: None })
+    }
+}
+
+/// Returns the index in `registry` of the renderer named by the command-line
+/// flag `--renderer <name>`, or of the first renderer if there is no such flag.
+fn choose_renderer(registry: &RendererRegistry) -> Result<usize> {
+    let mut args = ::std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg != "--renderer" {
+            continue
+        }
+        let name = args.next()
+            .ok_or_else(|| anyhow!("--renderer requires a name"))?;
+        return registry.names()
+            .position(|n| n == name)
+            .ok_or_else(|| anyhow!(
+                "unknown renderer {name:?}, expected one of: {}",
+                registry.names().collect::<Vec<_>>().join(", ")
+            ))
+    }
+    Ok(0)
+}
+
+fn construct_scene() -> Result<Scene<'static>> {
+    let mut scene = Scene {
         background_color: Color::BLACK,
         ambient_color: Color::WHITE * 0.05,
-        camera: Camera {
-            pos: Vector3::new(-5.0, 2.5, 0.0),
-            forward: Vector3::new(3.0f64.sqrt() / 2.0, -0.5, 0.0),
-            up: Vector3::new(0.5, 3.0f64.sqrt() / 2.0, 0.0),
-        },
+        // look along the x-axis, tilted 30° downwards
+        camera: Camera::from_yaw_pitch_roll(
+            Vector3::new(-5.0, 2.5, 0.0),
+            0.0,
+            -PI / 6.0,
+            0.0,
+            projection_at(0)
+        )?,
         lights: vec![
-            Light {
-                pos: Vector3::new(0.0, 0.0, 0.0),
-                diffuse_color: Color::WHITE,
-                specular_color: Color::WHITE * 0.5
-            }
+            Light::new(
+                Vector3::new(0.0, 0.0, 0.0),
+                Color::WHITE,
+                Color::WHITE * 0.5
+            )?
         ],
         shapes: vec![]
-    }
+    };
+    // place the spheres where they start, so that they are validated too
+    update_scene(&mut scene, 0.0);
+    scene.validate()?;
+    Ok(scene)
 }
 
-fn update_scene(scene: &mut Scene, t: f64) {
-    scene.shapes = vec![
-        Box::new(Sphere {
-            o: Vector3::new(t.cos(), 0.0, t.sin()),
-            r: 0.25,
-            ambient_color: Color::RED,
-            diffuse_color: Color::RED,
-            specular_color: Color::RED,
-            shininess: 2.0
-        }),
-        Box::new(Sphere {
-            o: 2.0 * Vector3::new((t / 2.0).cos(), 0.0, (t / 2.0).sin()),
-            r: 0.5,
-            ambient_color: Color::GREEN,
-            diffuse_color: Color::GREEN,
-            specular_color: Color::GREEN,
-            shininess: 2.0
-        }),
-        Box::new(Sphere {
-            o: 3.0 * Vector3::new((t / 3.0).cos(), 0.0, (t / 3.0).sin()),
-            r: 1.0,
-            ambient_color: Color::BLUE,
-            diffuse_color: Color::BLUE,
-            specular_color: Color::BLUE,
-            shininess: 2.0
+/// Places the spheres of `scene` where they are at time `t`, moving at the
+/// velocities they have then, so that they are blurred over the shutter
+/// interval of the camera.
+fn update_scene(scene: &mut Scene, t: Float) {
+    // the orbit of a sphere of radius r around the origin, with period 2πr
+    let orbit = |r: Float| {
+        let (sin, cos) = (t / r).sin_cos();
+        (r * Vector3::new(cos, 0.0, sin), Vector3::new(-sin, 0.0, cos))
+    };
+    let sphere = |r: Float, size: Float, color: Color| {
+        let (o, velocity) = orbit(r);
+        Box::new(Moving {
+            shape: Sphere {
+                o,
+                r: size,
+                ambient_color: color,
+                diffuse_color: color,
+                specular_color: color,
+                shininess: 2.0
+            },
+            motion: Motion::Linear { velocity }
         })
+    };
+    scene.shapes = vec![
+        sphere(1.0, 0.25, Color::RED),
+        sphere(2.0, 0.5, Color::GREEN),
+        sphere(3.0, 1.0, Color::BLUE)
     ];
 }
@@ -0,0 +1,126 @@
+use ::raytracing::Float;
+use ::raytracing::Region;
+use ::raytracing::TileScheduler;
+use ::raytracing::image::Image;
+use ::raytracing::render::Renderer;
+use ::raytracing::scene::Camera;
+use ::std::sync::atomic::AtomicBool;
+
+/// The number of samples per pixel after which accumulation stops.
+pub const MAX_SAMPLES: u32 = 1024;
+
+/// Accumulates jittered samples of an animated scene into an image, starting
+/// over whenever the time of the scene or its camera changes.
+///
+/// Samples may be restricted to a region of the image, leaving the rest of it
+/// black.
+pub struct Progressive {
+
+    /// The running average of the samples of each pixel.
+    image: Image,
+
+    /// The time and camera of the scene whose samples are in `image`, or
+    /// `None` if there are none.
+    state: Option<(Float, Camera)>,
+
+    /// The number of samples in each pixel of `image`.
+    samples: u32,
+
+    /// The scheduler of the tiles of each sample, which determines the region
+    /// of the image sampled.
+    scheduler: TileScheduler
+
+}
+
+impl Progressive {
+
+    /// Creates an accumulator with no samples for images of width `width` and
+    /// height `height`.
+    pub fn new(width: u32, height: u32) -> Self {
+        Progressive {
+            image: Image::new(width, height),
+            state: None,
+            samples: 0,
+            scheduler: TileScheduler::default()
+        }
+    }
+
+    /// Returns the running average of the samples accumulated so far.
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Returns the region of the image sampled, or `None` if it is the whole
+    /// image.
+    pub fn region(&self) -> Option<Region> {
+        self.scheduler.region
+    }
+
+    /// Discards the samples accumulated so far and restricts samples to
+    /// `region` of the image, or to the whole image if `region` is `None`.
+    pub fn set_region(&mut self, region: Option<Region>) {
+        self.scheduler.region = region;
+        self.reset();
+    }
+
+    /// Returns the number of samples accumulated in each pixel so far.
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Returns whether the samples accumulated so far are of the scene at time
+    /// `t` through `camera`.
+    pub fn is_current(&self, t: Float, camera: &Camera) -> bool {
+        self.state.as_ref()
+            .is_some_and(|(t2, camera2)| *t2 == t && camera2 == camera)
+    }
+
+    /// Returns whether no more samples of the scene at time `t` through
+    /// `camera` will be accumulated.
+    pub fn is_converged(&self, t: Float, camera: &Camera) -> bool {
+        self.samples >= MAX_SAMPLES && self.is_current(t, camera)
+    }
+
+    /// Discards the samples accumulated so far.
+    pub fn reset(&mut self) {
+        self.image.clear();
+        self.state = None;
+        self.samples = 0;
+    }
+
+    /// Discards the samples accumulated so far and resizes the image to width
+    /// `width` and height `height`, sampling the whole of it.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.image = Image::new(width, height);
+        self.set_region(None);
+    }
+
+    /// Uses `renderer`, which renders the scene at time `t` through `camera`,
+    /// to add another sample to each pixel, first discarding the samples
+    /// accumulated so far if either has changed since.
+    ///
+    /// Does nothing once `MAX_SAMPLES` samples have been accumulated. If
+    /// `renderer` fails, the pixels sampled before it did keep their samples,
+    /// but the sample is not counted.
+    pub fn render<R>(
+        &mut self,
+        renderer: &R,
+        t: Float,
+        camera: &Camera
+    ) -> Result<(), R::CastError>
+        where R: Renderer + Sync
+    {
+        if !self.is_current(t, camera) {
+            self.reset();
+            self.state = Some((t, camera.clone()));
+        } else if self.is_converged(t, camera) {
+            return Ok(())
+        }
+        let cancel = AtomicBool::new(false);
+        self.scheduler
+            .render_image(renderer, &mut self.image, |_| {}, &cancel)?;
+        self.samples += 1;
+        Ok(())
+    }
+
+}
@@ -0,0 +1,113 @@
+use ::raytracing::Region;
+use ::winit::dpi::PhysicalPosition;
+use ::winit::event::ElementState;
+use ::winit::event::MouseButton;
+use ::winit::event::WindowEvent;
+
+/// The color of the outline of a region being selected, as an RGBA pixel.
+const OUTLINE: [u8; 4] = [255, 255, 0, 255];
+
+/// Selection of a region of the window to render with the mouse.
+///
+/// Dragging with the right mouse button selects the region between the
+/// points where the drag starts and ends, and clicking with it without
+/// dragging selects the whole window again.
+#[derive(Default)]
+pub struct Selection {
+
+    /// The region selected, or `None` if it is the whole window.
+    selected: Option<Region>,
+
+    /// The position of the cursor when the drag in progress started, if any.
+    anchor: Option<PhysicalPosition<f64>>,
+
+    /// The last known position of the cursor, if it is in the window.
+    cursor: Option<PhysicalPosition<f64>>
+
+}
+
+impl Selection {
+
+    /// Returns the region selected, or `None` if it is the whole window.
+    pub fn selected(&self) -> Option<Region> {
+        self.selected
+    }
+
+    /// Returns the region between the point where the drag in progress
+    /// started and the cursor, or `None` if there is no such drag.
+    pub fn dragging(&self) -> Option<Region> {
+        match (self.anchor, self.cursor) {
+            (Some(anchor), Some(cursor)) => Some(between(anchor, cursor)),
+            _ => None
+        }
+    }
+
+    /// Updates the state of this selection in response to `event`, returning
+    /// whether a region was selected, even if it is the same one as before.
+    pub fn handle_event(&mut self, event: &WindowEvent) -> bool {
+        match *event {
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Right,
+                ..
+            } => {
+                self.anchor = self.cursor;
+                false
+            },
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Right,
+                ..
+            } => {
+                let Some(region) = self.dragging() else {
+                    return false
+                };
+                self.anchor = None;
+                // a click without dragging selects the whole window
+                self.selected = (region.area() > 0).then_some(region);
+                true
+            },
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = Some(position);
+                false
+            },
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor = None;
+                self.anchor = None;
+                false
+            },
+            _ => false
+        }
+    }
+
+}
+
+/// Returns the region of pixels between the points `a` and `b`.
+fn between(a: PhysicalPosition<f64>, b: PhysicalPosition<f64>) -> Region {
+    let (x0, x1) = (a.x.min(b.x).max(0.0), a.x.max(b.x).max(0.0));
+    let (y0, y1) = (a.y.min(b.y).max(0.0), a.y.max(b.y).max(0.0));
+    Region {
+        x: x0 as u32,
+        y: y0 as u32,
+        width: (x1 - x0) as u32,
+        height: (y1 - y0) as u32
+    }
+}
+
+/// Draws the outline of `region` onto `frame`, an RGBA image of width `width`,
+/// clipping it to the frame.
+pub fn draw_outline(region: &Region, frame: &mut [u8], width: u32) {
+    let height = (frame.len() / 4) as u32 / width.max(1);
+    let Some(region) = region.clamped(width, height) else {
+        return
+    };
+    let (right, bottom) = (region.x + region.width, region.y + region.height);
+    for (x, y) in region.pixels() {
+        let edge = x == region.x || x + 1 == right
+            || y == region.y || y + 1 == bottom;
+        if edge {
+            let i = 4 * (y as usize * width as usize + x as usize);
+            frame[i..i + 4].copy_from_slice(&OUTLINE);
+        }
+    }
+}
@@ -7,28 +7,31 @@ use std::ops::Mul;
 use std::ops::MulAssign;
 use std::ops::Sub;
 use std::ops::SubAssign;
+use crate::Error;
+use crate::Float;
+use crate::OutputTransform;
 
 /// A color.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Color {
 
-    /// The red component of this color.
+    /// The red component of this color, in linear light.
     ///
     /// 0.0 corresponds to a byte value of 0, and 1.0 corresponds to a byte
-    /// value of 255.
-    pub r: f64,
+    /// value of 255, when encoded with the default `OutputTransform`.
+    pub r: Float,
     
-    /// The green component of this color.
+    /// The green component of this color, in linear light.
     ///
     /// 0.0 corresponds to a byte value of 0, and 1.0 corresponds to a byte
-    /// value of 255.
-    pub g: f64,
+    /// value of 255, when encoded with the default `OutputTransform`.
+    pub g: Float,
 
-    /// The blue component of this color.
+    /// The blue component of this color, in linear light.
     ///
     /// 0.0 corresponds to a byte value of 0, and 1.0 corresponds to a byte
-    /// value of 255.
-    pub b: f64
+    /// value of 255, when encoded with the default `OutputTransform`.
+    pub b: Float
 
 }
 
@@ -50,6 +53,29 @@ impl Color {
 
     pub const WHITE: Color = Color { r: 1.0, g: 1.0, b: 1.0 };
 
+    /// Returns the relative luminance of this color, as defined by
+    /// ITU-R BT.709.
+    pub fn luminance(&self) -> Float {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// Returns whether every component of this color is finite.
+    pub fn is_finite(&self) -> bool {
+        self.r.is_finite() && self.g.is_finite() && self.b.is_finite()
+    }
+
+    /// Returns an error naming this color `name` if any of its components is
+    /// infinite, NaN or negative.
+    pub(crate) fn validate(&self, name: &'static str) -> Result<(), Error> {
+        if !self.is_finite() {
+            Err(Error::NotFinite(name))
+        } else if self.r.min(self.g).min(self.b) < 0.0 {
+            Err(Error::Negative(name))
+        } else {
+            Ok(())
+        }
+    }
+
 }
 
 impl Add for Color {
@@ -76,11 +102,11 @@ impl AddAssign for Color {
 
 }
 
-impl Div<f64> for Color {
+impl Div<Float> for Color {
 
     type Output = Self;
 
-    fn div(self, rhs: f64) -> Self::Output {
+    fn div(self, rhs: Float) -> Self::Output {
         Color {
             r: self.r / rhs,
             g: self.g / rhs,
@@ -90,9 +116,9 @@ impl Div<f64> for Color {
 
 }
 
-impl DivAssign<f64> for Color {
+impl DivAssign<Float> for Color {
 
-    fn div_assign(&mut self, other: f64) {
+    fn div_assign(&mut self, other: Float) {
         self.r /= other;
         self.g /= other;
         self.b /= other;
@@ -102,13 +128,10 @@ impl DivAssign<f64> for Color {
 
 impl From<Color> for [u8; 4] {
 
+    /// Converts `color` into an opaque, sRGB-encoded 8-bit RGBA pixel with the
+    /// default `OutputTransform`, which clips it.
     fn from(color: Color) -> Self {
-        [
-            (color.r * 256.0) as u8,
-            (color.g * 256.0) as u8,
-            (color.b * 256.0) as u8,
-            255
-        ]
+        OutputTransform::default().encode(color, 0, 0)
     }
 
 }
@@ -137,11 +160,11 @@ impl MulAssign<Color> for Color {
 
 }
 
-impl Mul<f64> for Color {
+impl Mul<Float> for Color {
 
     type Output = Self;
 
-    fn mul(self, rhs: f64) -> Self::Output {
+    fn mul(self, rhs: Float) -> Self::Output {
         Color {
             r: self.r * rhs,
             g: self.g * rhs,
@@ -151,9 +174,9 @@ impl Mul<f64> for Color {
 
 }
 
-impl MulAssign<f64> for Color {
+impl MulAssign<Float> for Color {
 
-    fn mul_assign(&mut self, other: f64) {
+    fn mul_assign(&mut self, other: Float) {
         self.r *= other;
         self.g *= other;
         self.b *= other;
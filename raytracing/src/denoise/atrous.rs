@@ -0,0 +1,217 @@
+use ::nalgebra::Vector3;
+use ::rayon::iter::IntoParallelIterator;
+use ::rayon::iter::ParallelIterator;
+use crate::Color;
+use crate::Float;
+use crate::image::Image;
+use crate::image::LayeredImage;
+use crate::render::Aov;
+
+/// The weights of the B3 spline kernel along each axis, by offset from the
+/// center from -2 to 2.
+const KERNEL: [(i64, Float); 5] = [
+    (-2, 1.0 / 16.0),
+    (-1, 1.0 / 4.0),
+    (0, 3.0 / 8.0),
+    (1, 1.0 / 4.0),
+    (2, 1.0 / 16.0)
+];
+
+/// The smallest component of an albedo which colors are divided by, below
+/// which they are left as they are.
+const MIN_ALBEDO: Float = 1e-3;
+
+/// A denoiser which applies the edge-avoiding à-trous wavelet transform,
+/// repeatedly blurring an image with a kernel whose taps spread twice as far
+/// each pass, while stopping at edges in its colors, normals and depths.
+///
+/// The normals and depths come from the layers of the image for `Aov::Normal`
+/// and `Aov::Depth`, and colors are divided by the layer for `Aov::Albedo`
+/// while filtering so that the detail of textures is kept. Each layer which
+/// is missing is ignored.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AtrousDenoiser {
+
+    /// The number of passes, which filters over a radius of about
+    /// `2 ^ (iterations + 1)` pixels.
+    pub iterations: u32,
+
+    /// How much the colors of pixels may differ while being blurred
+    /// together, which halves with each pass.
+    pub sigma_color: Float,
+
+    /// The power of the cosine of the angle between the normals of pixels
+    /// which weighs them, so that larger values preserve creases more.
+    pub sigma_normal: Float,
+
+    /// How much the depths of pixels may differ relative to their depths,
+    /// per pixel between them, while being blurred together.
+    pub sigma_depth: Float
+
+}
+
+impl Default for AtrousDenoiser {
+
+    /// Returns the denoiser which filters over five passes, with moderate
+    /// edge-stopping.
+    fn default() -> Self {
+        AtrousDenoiser {
+            iterations: 5,
+            sigma_color: 1.0,
+            sigma_normal: 64.0,
+            sigma_depth: 0.02
+        }
+    }
+
+}
+
+/// The features of the pixels of an image which guide a denoiser, row by row
+/// from the top.
+struct Guides {
+
+    /// The unit normals of the pixels, or zero where there is no surface.
+    normals: Option<Vec<Vector3<Float>>>,
+
+    /// The depths of the pixels.
+    depths: Option<Vec<Float>>
+
+}
+
+impl AtrousDenoiser {
+
+    /// Returns the beauty of `image` with its noise removed, guided by its
+    /// layers.
+    pub fn denoise(&self, image: &LayeredImage) -> Image {
+        let (width, height) = (image.width(), image.height());
+        let layer = |aov| image.layer(aov).map(|l| l.pixels().collect());
+        let albedos: Option<Vec<Color>> = layer(Aov::Albedo);
+        let guides = Guides {
+            normals: image.layer(Aov::Normal).map(|l| {
+                l.pixels()
+                    .map(|c| {
+                        Vector3::new(c.r, c.g, c.b)
+                            .try_normalize(0.0)
+                            .unwrap_or_else(Vector3::zeros)
+                    })
+                    .collect()
+            }),
+            depths: image.layer(Aov::Depth).map(|l| {
+                l.pixels().map(|c| c.r).collect()
+            })
+        };
+        // the factors by which colors are divided while filtering
+        let factors: Vec<Color> = match albedos {
+            Some(albedos) => albedos.into_iter().map(demodulation).collect(),
+            None => vec![Color::WHITE; (width * height) as usize]
+        };
+        let mut colors: Vec<Color> = image.beauty.pixels()
+            .zip(&factors)
+            .map(|(color, factor)| Color {
+                r: color.r / factor.r,
+                g: color.g / factor.g,
+                b: color.b / factor.b
+            })
+            .collect();
+        for i in 0..self.iterations {
+            colors = self.pass(&colors, &guides, width, height, i);
+        }
+        let pixels = colors.into_iter()
+            .zip(factors)
+            .map(|(color, factor)| color * factor)
+            .collect();
+        Image::from_pixels(width, height, pixels).unwrap()
+    }
+
+    /// Returns `colors`, the colors of an image of width `width` and height
+    /// `height`, blurred by the pass at index `i`, whose taps are `2 ^ i`
+    /// pixels apart.
+    fn pass(
+        &self,
+        colors: &[Color],
+        guides: &Guides,
+        width: u32,
+        height: u32,
+        i: u32
+    ) -> Vec<Color> {
+        let step: i64 = 1 << i;
+        let sigma_color = self.sigma_color * (0.5 as Float).powi(i as i32);
+        (0..colors.len()).into_par_iter()
+            .map(|p| {
+            let (px, py) = (p as i64 % width as i64, p as i64 / width as i64);
+            let mut sum = Color::BLACK;
+            let mut total = 0.0;
+            for (dy, hy) in KERNEL {
+                for (dx, hx) in KERNEL {
+                    let (qx, qy) = (px + dx * step, py + dy * step);
+                    if qx < 0 || qy < 0
+                        || qx >= width as i64 || qy >= height as i64 {
+                        continue
+                    }
+                    let q = (qy * width as i64 + qx) as usize;
+                    let weight = hx * hy
+                        * color_weight(colors[p], colors[q], sigma_color)
+                        * self.guide_weight(guides, p, q, step as Float);
+                    sum += colors[q] * weight;
+                    total += weight;
+                }
+            }
+            // the center always has a positive weight
+            sum / total
+        })
+            .collect()
+    }
+
+    /// Returns the weight by which the normals and depths in `guides` of the
+    /// pixels at indices `p` and `q`, which are `distance` pixels apart,
+    /// scale the contribution of `q` to `p`.
+    fn guide_weight(
+        &self,
+        guides: &Guides,
+        p: usize,
+        q: usize,
+        distance: Float
+    ) -> Float {
+        let mut weight = 1.0;
+        if let Some(normals) = &guides.normals {
+            let (np, nq) = (normals[p], normals[q]);
+            // pixels without surfaces are left to the depths
+            if np != Vector3::zeros() && nq != Vector3::zeros() {
+                weight *= np.dot(&nq).max(0.0).powf(self.sigma_normal);
+            }
+        }
+        if let Some(depths) = &guides.depths {
+            let (zp, zq) = (depths[p], depths[q]);
+            weight *= match (zp.is_finite(), zq.is_finite()) {
+                (true, true) => {
+                    let scale = self.sigma_depth * zp.abs() * distance;
+                    (-(zp - zq).abs() / scale.max(Float::EPSILON)).exp()
+                },
+                // the background only blurs with itself
+                (false, false) => 1.0,
+                _ => 0.0
+            };
+        }
+        weight
+    }
+
+}
+
+/// Returns the weight by which the difference between the colors `a` and `b`
+/// scales the contribution of one to the other.
+fn color_weight(a: Color, b: Color, sigma: Float) -> Float {
+    let d = a - b;
+    let dist_sq = d.r * d.r + d.g * d.g + d.b * d.b;
+    (-dist_sq / (sigma * sigma).max(Float::EPSILON)).exp()
+}
+
+/// Returns the factors by which colors of surfaces of albedo `albedo` are
+/// divided while filtering, which are 1.0 where the albedo is too dark to
+/// divide by.
+fn demodulation(albedo: Color) -> Color {
+    let factor = |a: Float| if a > MIN_ALBEDO { a } else { 1.0 };
+    Color {
+        r: factor(albedo.r),
+        g: factor(albedo.g),
+        b: factor(albedo.b)
+    }
+}
@@ -0,0 +1,7 @@
+//! Contains denoisers, which remove the noise from images rendered with few
+//! samples by stochastic renderers, guided by their arbitrary output
+//! variables.
+
+mod atrous;
+
+pub use atrous::*;
@@ -0,0 +1,106 @@
+use ::nalgebra::Vector3;
+use ::std::fmt;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use crate::Color;
+use crate::Float;
+use crate::scene::CameraError;
+
+/// An error arising from constructing a scene from invalid parts or from
+/// rendering a scene which turns out to be invalid.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+
+    /// A camera has a degenerate orientation.
+    Camera(CameraError),
+
+    /// The quantity named is infinite or NaN.
+    NotFinite(&'static str),
+
+    /// The quantity named is negative.
+    Negative(&'static str),
+
+    /// The radius of a sphere is zero or negative.
+    NonPositiveRadius(Float),
+
+    /// The vertices of a triangle are collinear, so that it has no normal.
+    CollinearVertices,
+
+    /// The times of the keyframes of a motion do not strictly increase.
+    UnorderedKeyframes,
+
+    /// The shape at `index` in a scene is invalid because of `cause`.
+    InvalidShape { index: usize, cause: Box<Error> },
+
+    /// The light source at `index` in a scene is invalid because of `cause`.
+    InvalidLight { index: usize, cause: Box<Error> },
+
+    /// The normal of the shape at `index` in a scene is zero or not finite at
+    /// `point`, where a ray intersects it.
+    InvalidNormal { index: usize, point: Vector3<Float> },
+
+    /// A renderer computed `color`, which is infinite or NaN, along a ray
+    /// which first intersects the shape at `index` in a scene, if any.
+    NonFiniteRadiance { color: Color, index: Option<usize> }
+
+}
+
+impl Display for Error {
+
+    /// Formats a description of this error, including its cause, if any.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Camera(e) => write!(f, "invalid camera: {e}"),
+            Error::NotFinite(name) => write!(f, "{name} is not finite"),
+            Error::Negative(name) => write!(f, "{name} is negative"),
+            Error::NonPositiveRadius(r) => {
+                write!(f, "sphere radius {r} is not positive")
+            },
+            Error::CollinearVertices => {
+                f.write_str("triangle vertices are collinear")
+            },
+            Error::UnorderedKeyframes => {
+                f.write_str("motion keyframe times do not strictly increase")
+            },
+            Error::InvalidShape { index, cause } => {
+                write!(f, "invalid shape at index {index}: {cause}")
+            },
+            Error::InvalidLight { index, cause } => {
+                write!(f, "invalid light at index {index}: {cause}")
+            },
+            Error::InvalidNormal { index, point } => write!(
+                f,
+                "shape at index {index} has an invalid normal at ({}, {}, {})",
+                point.x,
+                point.y,
+                point.z
+            ),
+            Error::NonFiniteRadiance { color, index } => {
+                write!(
+                    f,
+                    "radiance ({}, {}, {}) is not finite",
+                    color.r,
+                    color.g,
+                    color.b
+                )?;
+                match index {
+                    Some(index) => write!(f, " on shape at index {index}"),
+                    None => Ok(())
+                }
+            }
+        }
+    }
+
+}
+
+// the description of this error includes its cause, so it has no source
+impl ::std::error::Error for Error {}
+
+impl From<CameraError> for Error {
+
+    /// Returns an error for a camera with the problem `e`.
+    fn from(e: CameraError) -> Self {
+        Error::Camera(e)
+    }
+
+}
@@ -0,0 +1,27 @@
+/// The floating-point type of the quantities of geometry and color throughout
+/// this crate, which is `f32` when the feature "f32" is enabled, trading
+/// precision for speed and memory, and `f64` otherwise.
+pub type Float = precision::Float;
+
+/// Mathematical constants of type `Float`.
+pub use precision::consts;
+
+/// The distance by which rays leaving a surface are offset from it, relative
+/// to the magnitude of the point they leave from, so that they do not
+/// intersect it again, which must be coarser at lower precision.
+pub(crate) const SURFACE_OFFSET: Float = precision::SURFACE_OFFSET;
+
+// the definitions which differ between double and single precision
+#[cfg(not(feature = "f32"))]
+mod precision {
+    pub type Float = f64;
+    pub use ::std::f64::consts;
+    pub const SURFACE_OFFSET: Float = 1.0e-7;
+}
+
+#[cfg(feature = "f32")]
+mod precision {
+    pub type Float = f32;
+    pub use ::std::f32::consts;
+    pub const SURFACE_OFFSET: Float = 1.0e-3;
+}
@@ -0,0 +1,182 @@
+use crate::Color;
+use crate::Float;
+use crate::image::Image;
+
+/// The luminance below which pixels are treated as having this luminance when
+/// estimating their relative error, since noise in darker pixels is barely
+/// visible.
+const MIN_LUMINANCE: Float = 0.05;
+
+/// The settings of adaptive sampling, which spends samples only on the pixels
+/// of an image whose noise is still visible.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AdaptiveSampling {
+
+    /// The number of samples every pixel takes before its error is estimated.
+    pub min_samples: u32,
+
+    /// The number of samples after which a pixel takes no more, however noisy
+    /// it is.
+    pub max_samples: u32,
+
+    /// The estimated relative error in the luminance of a pixel below which
+    /// it takes no more samples.
+    pub threshold: Float
+
+}
+
+impl Default for AdaptiveSampling {
+
+    /// Returns the settings which take between 16 and 1024 samples per pixel,
+    /// until the relative error of each is below 1%.
+    fn default() -> Self {
+        AdaptiveSampling {
+            min_samples: 16,
+            max_samples: 1024,
+            threshold: 0.01
+        }
+    }
+
+}
+
+/// An image which accumulates samples adaptively, tracking the variance of
+/// the luminance of the samples of each pixel to decide whether it needs
+/// more.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveImage {
+
+    /// The settings which decide when pixels stop taking samples.
+    settings: AdaptiveSampling,
+
+    /// The average of the samples of each pixel.
+    image: Image,
+
+    /// The sums of the luminances of the samples of each pixel and of their
+    /// squares, row by row from the top.
+    moments: Vec<(Float, Float)>,
+
+    /// The number of samples of each pixel, row by row from the top.
+    samples: Vec<u32>
+
+}
+
+impl AdaptiveImage {
+
+    /// Creates a black image of width `width` and height `height`, none of
+    /// whose pixels have any samples, which samples adaptively according to
+    /// `settings`.
+    pub fn new(width: u32, height: u32, settings: AdaptiveSampling) -> Self {
+        let len = width as usize * height as usize;
+        AdaptiveImage {
+            settings,
+            image: Image::new(width, height),
+            moments: vec![(0.0, 0.0); len],
+            samples: vec![0; len]
+        }
+    }
+
+    /// Returns the settings which decide when pixels stop taking samples.
+    pub fn settings(&self) -> &AdaptiveSampling {
+        &self.settings
+    }
+
+    /// Returns the width of this image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    /// Returns the height of this image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    /// Returns the average of the samples of each pixel.
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Returns the index of the pixel in column `x` and row `y`.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width() && y < self.height(), "pixel out of bounds");
+        y as usize * self.width() as usize + x as usize
+    }
+
+    /// Adds a sample of `color` to the pixel in column `x` and row `y`.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn add_sample(&mut self, x: u32, y: u32, color: Color) {
+        let i = self.index(x, y);
+        let luminance = color.luminance();
+        let (sum, sum_sq) = &mut self.moments[i];
+        *sum += luminance;
+        *sum_sq += luminance * luminance;
+        self.samples[i] += 1;
+        self.image.add_sample(x, y, color, 1.0);
+    }
+
+    /// Returns the number of samples of the pixel in column `x` and row `y`.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn samples(&self, x: u32, y: u32) -> u32 {
+        self.samples[self.index(x, y)]
+    }
+
+    /// Returns the estimated error in the luminance of the pixel in column `x`
+    /// and row `y` relative to the luminance, which is the standard error of
+    /// the mean of its samples, or infinity if it has fewer than two samples.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn error(&self, x: u32, y: u32) -> Float {
+        let i = self.index(x, y);
+        let n = self.samples[i] as Float;
+        if n < 2.0 {
+            return Float::INFINITY
+        }
+        let (sum, sum_sq) = self.moments[i];
+        let mean = sum / n;
+        let variance = ((sum_sq - sum * mean) / (n - 1.0)).max(0.0);
+        (variance / n).sqrt() / mean.max(MIN_LUMINANCE)
+    }
+
+    /// Returns whether the pixel in column `x` and row `y` takes no more
+    /// samples, because it has taken the most allowed or, having taken the
+    /// fewest required, its error is below the threshold.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn is_converged(&self, x: u32, y: u32) -> bool {
+        let samples = self.samples(x, y);
+        samples >= self.settings.max_samples
+            || (samples >= self.settings.min_samples
+                && self.error(x, y) <= self.settings.threshold)
+    }
+
+    /// Returns the number of pixels which take more samples.
+    pub fn active_pixels(&self) -> usize {
+        let (width, height) = (self.width(), self.height());
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| !self.is_converged(x, y))
+            .count()
+    }
+
+    /// Returns a map of the number of samples each pixel has taken, the color
+    /// of which is the fraction of the most allowed, between black for none
+    /// and white for all of them.
+    pub fn sample_map(&self) -> Image {
+        let max = self.settings.max_samples.max(1) as Float;
+        let pixels = self.samples.iter()
+            .map(|&n| Color::WHITE * (n as Float / max))
+            .collect();
+        Image::from_pixels(self.width(), self.height(), pixels).unwrap()
+    }
+
+    /// Removes the samples of every pixel, making this image black.
+    pub fn clear(&mut self) {
+        self.image.clear();
+        self.moments.fill((0.0, 0.0));
+        self.samples.fill(0);
+    }
+
+}
@@ -0,0 +1,37 @@
+use crate::Float;
+use crate::image::Image;
+
+/// A single named channel of an image, holding one value per pixel, such as
+/// the alpha channel or the depth of each pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Channel {
+
+    /// The name of this channel.
+    ///
+    /// Names of the form `layer.R` group channels into layers in OpenEXR
+    /// files.
+    pub name: String,
+
+    /// The values of this channel, row by row from the top.
+    pub values: Vec<Float>
+
+}
+
+impl Channel {
+
+    /// Returns the red, green and blue components of the pixels of `image` as
+    /// three channels named `layer.R`, `layer.G` and `layer.B`.
+    pub fn split(layer: &str, image: &Image) -> [Channel; 3] {
+        let pixels: Vec<_> = image.pixels().collect();
+        let channel = |suffix: &str, f: fn(&crate::Color) -> Float| Channel {
+            name: format!("{}.{}", layer, suffix),
+            values: pixels.iter().map(f).collect()
+        };
+        [
+            channel("R", |c| c.r),
+            channel("G", |c| c.g),
+            channel("B", |c| c.b)
+        ]
+    }
+
+}
@@ -0,0 +1,229 @@
+use ::exr::prelude::AnyChannel;
+use ::exr::prelude::AnyChannels;
+use ::exr::prelude::Encoding;
+use ::exr::prelude::FlatSamples;
+use ::exr::prelude::Image as ExrImage;
+use ::exr::prelude::Layer;
+use ::exr::prelude::LayerAttributes;
+use ::exr::prelude::ReadChannels;
+use ::exr::prelude::ReadLayers;
+use ::exr::prelude::SmallVec;
+use ::exr::prelude::Text;
+use ::exr::prelude::WritableImage;
+use ::exr::prelude::f16;
+use ::exr::prelude::read;
+use ::std::path::Path;
+use crate::Color;
+use crate::Float;
+use crate::image::Channel;
+use crate::image::Image;
+
+pub use ::exr::error::Error as ExrError;
+
+/// The precision with which the values of channels are stored in an OpenEXR
+/// file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExrPrecision {
+
+    /// 16-bit floating-point numbers, which are precise enough for colors and
+    /// take half the space.
+    Half,
+
+    /// 32-bit floating-point numbers, which are better suited to data such as
+    /// depths and positions.
+    Float
+
+}
+
+/// The contents of an OpenEXR file read by `read_exr`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExrContents {
+
+    /// The image made of the `R`, `G` and `B` channels of the file.
+    ///
+    /// A missing channel is taken to be zero, unless the file only has a `Y`
+    /// (luminance) channel, which is then used for all three.
+    pub image: Image,
+
+    /// The `A` channel of the file, if it has one.
+    pub alpha: Option<Vec<Float>>,
+
+    /// The other channels of the file, such as arbitrary output variables.
+    pub channels: Vec<Channel>
+
+}
+
+/// Writes `image` to the OpenEXR file at `path` with the precision
+/// `precision`, along with the alpha channel `alpha` and the extra channels
+/// `channels`, if any.
+///
+/// Each channel must have one value per pixel of `image`.
+// casting values to `f32` does nothing with the feature "f32"
+#[cfg_attr(feature = "f32", allow(clippy::unnecessary_cast))]
+pub fn write_exr<P>(
+    path: P,
+    image: &Image,
+    alpha: Option<&[Float]>,
+    channels: &[Channel],
+    precision: ExrPrecision
+) -> Result<(), ExrError>
+    where P: AsRef<Path>
+{
+    let len = image.width() as usize * image.height() as usize;
+    let samples = |values: &mut dyn Iterator<Item = Float>| match precision {
+        ExrPrecision::Half => FlatSamples::F16(values
+            .map(|v| f16::from_f32(v as f32))
+            .collect()),
+        ExrPrecision::Float => FlatSamples::F32(values
+            .map(|v| v as f32)
+            .collect())
+    };
+    let mut list = Vec::with_capacity(4 + channels.len());
+    let pixels: Vec<Color> = image.pixels().collect();
+    list.push(AnyChannel::new("R", samples(&mut pixels.iter().map(|c| c.r))));
+    list.push(AnyChannel::new("G", samples(&mut pixels.iter().map(|c| c.g))));
+    list.push(AnyChannel::new("B", samples(&mut pixels.iter().map(|c| c.b))));
+    if let Some(alpha) = alpha {
+        if alpha.len() != len {
+            return Err(ExrError::Invalid("alpha size mismatch".into()))
+        }
+        list.push(AnyChannel::new("A", samples(&mut alpha.iter().copied())));
+    }
+    for channel in channels {
+        if channel.values.len() != len {
+            return Err(ExrError::Invalid("channel size mismatch".into()))
+        }
+        let name = Text::new_or_none(&channel.name)
+            .ok_or_else(|| ExrError::Invalid("channel name".into()))?;
+        let mut values = channel.values.iter().copied();
+        list.push(AnyChannel::new(name, samples(&mut values)));
+    }
+    let layer = Layer::new(
+        (image.width() as usize, image.height() as usize),
+        LayerAttributes::default(),
+        Encoding::SMALL_LOSSLESS,
+        AnyChannels::sort(SmallVec::from_vec(list))
+    );
+    ExrImage::from_layer(layer).write().to_file(path)
+}
+
+/// Reads the first layer of the OpenEXR file at `path` at its largest
+/// resolution.
+pub fn read_exr<P>(path: P) -> Result<ExrContents, ExrError>
+    where P: AsRef<Path>
+{
+    let exr = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .first_valid_layer()
+        .all_attributes()
+        .from_file(path)?;
+    let layer = exr.layer_data;
+    let (width, height) = (layer.size.0, layer.size.1);
+    let len = width * height;
+    let mut rgb: [Option<Vec<Float>>; 3] = [None, None, None];
+    let (mut luma, mut alpha) = (None, None);
+    let mut channels = Vec::new();
+    for channel in layer.channel_data.list {
+        let name = channel.name.to_string();
+        let values: Vec<Float> = channel.sample_data
+            .values_as_f32()
+            .map(|v| v as Float)
+            .collect();
+        // the color channels must not be subsampled, as pixels index them
+        let color = matches!(name.as_str(), "R" | "G" | "B" | "Y" | "A");
+        if color && values.len() != len {
+            return Err(ExrError::Invalid(format!(
+                "channel {name} has {} values for {len} pixels",
+                values.len()
+            ).into()))
+        }
+        match name.as_str() {
+            "R" => rgb[0] = Some(values),
+            "G" => rgb[1] = Some(values),
+            "B" => rgb[2] = Some(values),
+            "Y" => luma = Some(values),
+            "A" => alpha = Some(values),
+            _ => channels.push(Channel { name, values })
+        }
+    }
+    let [r, g, b] = match (rgb, luma) {
+        ([None, None, None], Some(y)) => [y.clone(), y.clone(), y],
+        (rgb, luma) => {
+            // a luminance channel alongside colors is just another channel
+            if let Some(values) = luma {
+                channels.push(Channel { name: "Y".to_string(), values });
+            }
+            rgb.map(|c| c.unwrap_or_else(|| vec![0.0; len]))
+        }
+    };
+    let pixels = (0..len)
+        .map(|i| Color { r: r[i], g: g[i], b: b[i] })
+        .collect();
+    let image = Image::from_pixels(width as u32, height as u32, pixels)
+        .ok_or_else(|| ExrError::Invalid("channel size mismatch".into()))?;
+    Ok(ExrContents { image, alpha, channels })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ::std::env;
+    use ::std::fs;
+    use ::std::path::PathBuf;
+    use ::std::process;
+    use crate::Color;
+    use crate::Float;
+    use crate::image::Channel;
+    use crate::image::Image;
+    use super::ExrPrecision;
+    use super::read_exr;
+    use super::write_exr;
+
+    /// Returns a path for a temporary file named after `name`.
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("raytracing-{}-{name}", process::id()))
+    }
+
+    /// Returns a 3 by 2 image whose values are exact in half precision.
+    fn image() -> Image {
+        let pixels = (0..6)
+            .map(|i| {
+                let v = i as Float;
+                Color { r: v * 0.5, g: v * 0.25 + 1.0, b: 8.0 - v }
+            })
+            .collect();
+        Image::from_pixels(3, 2, pixels).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let alpha = vec![1.0, 0.5, 0.25, 0.0, 0.75, 1.0];
+        let channels = vec![Channel {
+            name: "Z".to_string(),
+            values: vec![1.5, 2.0, 2.5, 3.0, 3.5, 1024.0]
+        }];
+        for precision in [ExrPrecision::Half, ExrPrecision::Float] {
+            let path = temp_path(&format!("{precision:?}.exr"));
+            write_exr(&path, &image(), Some(&alpha), &channels, precision)
+                .unwrap();
+            let contents = read_exr(&path);
+            fs::remove_file(&path).unwrap();
+            let contents = contents.unwrap();
+            assert_eq!(contents.image, image());
+            assert_eq!(contents.alpha.as_ref(), Some(&alpha));
+            assert_eq!(contents.channels, channels);
+        }
+    }
+
+    #[test]
+    fn mismatched_alpha() {
+        let path = temp_path("alpha.exr");
+        let alpha = [1.0; 5];
+        let precision = ExrPrecision::Half;
+        assert!(write_exr(&path, &image(), Some(&alpha), &[], precision)
+            .is_err());
+    }
+
+}
@@ -0,0 +1,346 @@
+use ::rayon::iter::IndexedParallelIterator;
+use ::rayon::iter::IntoParallelRefMutIterator;
+use ::rayon::iter::ParallelIterator;
+use ::rayon::slice::ParallelSliceMut;
+use crate::Color;
+use crate::Float;
+use crate::OutputTransform;
+
+/// An image of linear colors, each pixel of which is the weighted average of
+/// the samples accumulated into it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+
+    /// The width of this image, in pixels.
+    width: u32,
+
+    /// The height of this image, in pixels.
+    height: u32,
+
+    /// The weighted sum of the samples of each pixel, row by row from the top.
+    sums: Vec<Color>,
+
+    /// The sum of the weights of the samples of each pixel, row by row from
+    /// the top.
+    weights: Vec<Float>
+
+}
+
+impl Image {
+
+    /// Creates a black image of width `width` and height `height`, none of
+    /// whose pixels have any samples.
+    pub fn new(width: u32, height: u32) -> Self {
+        let len = width as usize * height as usize;
+        Image {
+            width,
+            height,
+            sums: vec![Color::BLACK; len],
+            weights: vec![0.0; len]
+        }
+    }
+
+    /// Creates an image of width `width` and height `height` from `pixels`,
+    /// which are given row by row from the top.
+    ///
+    /// Returns `None` if the number of pixels is not `width * height`.
+    pub fn from_pixels(
+        width: u32,
+        height: u32,
+        pixels: Vec<Color>
+    ) -> Option<Self> {
+        if pixels.len() != width as usize * height as usize {
+            return None
+        }
+        let weights = vec![1.0; pixels.len()];
+        Some(Image { width, height, sums: pixels, weights })
+    }
+
+    /// Returns the width of this image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of this image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the index of the pixel in column `x` and row `y`.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "pixel out of bounds");
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Returns the color of the pixel in column `x` and row `y`, which is
+    /// black if it has no samples.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let i = self.index(x, y);
+        average(self.sums[i], self.weights[i])
+    }
+
+    /// Replaces the samples of the pixel in column `x` and row `y` with a
+    /// single sample of `color`.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        let i = self.index(x, y);
+        self.sums[i] = color;
+        self.weights[i] = 1.0;
+    }
+
+    /// Adds a sample of `color` with the weight `weight` to the pixel in
+    /// column `x` and row `y`.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn add_sample(&mut self, x: u32, y: u32, color: Color, weight: Float) {
+        let i = self.index(x, y);
+        self.sums[i] += color * weight;
+        self.weights[i] += weight;
+    }
+
+    /// Returns the sum of the weights of the samples of the pixel in column `x`
+    /// and row `y`.
+    ///
+    /// Panics if the pixel lies outside of this image.
+    pub fn weight(&self, x: u32, y: u32) -> Float {
+        self.weights[self.index(x, y)]
+    }
+
+    /// Removes the samples of every pixel, making this image black.
+    pub fn clear(&mut self) {
+        self.sums.fill(Color::BLACK);
+        self.weights.fill(0.0);
+    }
+
+    /// Returns an iterator over the colors of the pixels of this image, row by
+    /// row from the top.
+    pub fn pixels(&self) -> impl Iterator<Item = Color> + '_ {
+        self.sums.iter()
+            .zip(&self.weights)
+            .map(|(&sum, &weight)| average(sum, weight))
+    }
+
+    /// Returns a parallel iterator over the weighted sums of the samples of
+    /// the pixels of this image and the sums of their weights, row by row from
+    /// the top, through which samples can be accumulated in parallel.
+    pub fn samples_mut(
+        &mut self
+    ) -> impl IndexedParallelIterator<Item = (&mut Color, &mut Float)> {
+        self.sums.par_iter_mut().zip(self.weights.par_iter_mut())
+    }
+
+    /// Returns the part of this image of width `width` and height `height`
+    /// whose top-left pixel is in column `x` and row `y`.
+    ///
+    /// Returns `None` if that part does not lie entirely within this image.
+    pub fn crop(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32
+    ) -> Option<Image> {
+        if x.checked_add(width)? > self.width
+            || y.checked_add(height)? > self.height
+        {
+            return None
+        }
+        let mut cropped = Image::new(width, height);
+        if width == 0 || height == 0 {
+            // there are no rows to copy, and x may lie beyond the last column
+            return Some(cropped)
+        }
+        for row in 0..height {
+            let src = self.index(x, y + row);
+            let dst = row as usize * width as usize;
+            let len = width as usize;
+            cropped.sums[dst..dst + len]
+                .copy_from_slice(&self.sums[src..src + len]);
+            cropped.weights[dst..dst + len]
+                .copy_from_slice(&self.weights[src..src + len]);
+        }
+        Some(cropped)
+    }
+
+    /// Returns this image resampled to width `width` and height `height`.
+    ///
+    /// Each pixel is filtered with a tent filter which is wide enough to
+    /// cover the pixels it replaces when shrinking, and which interpolates
+    /// bilinearly when enlarging.
+    pub fn resize(&self, width: u32, height: u32) -> Image {
+        if self.width == 0 || self.height == 0 {
+            return Image::new(width, height)
+        }
+        let pixels: Vec<Color> = self.pixels().collect();
+        // resample the rows, then the columns
+        let wide = resample(&pixels, self.width, self.height, width, true);
+        let resized = resample(&wide, width, self.height, height, false);
+        Image::from_pixels(width, height, resized).unwrap()
+    }
+
+    /// Encodes this image with `transform` into `buf` as 8-bit RGBA pixels,
+    /// row by row from the top.
+    ///
+    /// Panics if the length of `buf` is not four times the number of pixels.
+    pub fn write_rgba8(&self, transform: &OutputTransform, buf: &mut [u8]) {
+        assert_eq!(buf.len(), 4 * self.sums.len(), "buffer size mismatch");
+        let width = self.width as usize;
+        buf.par_chunks_exact_mut(4)
+            .enumerate()
+            .for_each(|(i, pixel)| {
+                let color = average(self.sums[i], self.weights[i]);
+                let (x, y) = ((i % width) as u32, (i / width) as u32);
+                pixel.copy_from_slice(&transform.encode(color, x, y));
+            });
+    }
+
+    /// Returns this image encoded with `transform` as 8-bit RGBA pixels, row
+    /// by row from the top.
+    pub fn to_rgba8(&self, transform: &OutputTransform) -> Vec<u8> {
+        let mut buf = vec![0; 4 * self.sums.len()];
+        self.write_rgba8(transform, &mut buf);
+        buf
+    }
+
+}
+
+/// Returns the average color of samples with the weighted sum `sum` and the
+/// total weight `weight`, which is black if there are none.
+fn average(sum: Color, weight: Float) -> Color {
+    if weight > 0.0 { sum / weight } else { Color::BLACK }
+}
+
+/// Resamples the rows (if `horizontal`) or the columns of the image of width
+/// `width` and height `height` whose pixels are `pixels` to the length `len`.
+fn resample(
+    pixels: &[Color],
+    width: u32,
+    height: u32,
+    len: u32,
+    horizontal: bool
+) -> Vec<Color> {
+    let (src_len, lines) = if horizontal {
+        (width, height)
+    } else {
+        (height, width)
+    };
+    let scale = src_len as Float / len as Float;
+    // the radius of the filter, in source pixels
+    let radius = scale.max(1.0);
+    let at = |line: u32, i: u32| {
+        if horizontal {
+            pixels[(line * width + i) as usize]
+        } else {
+            pixels[(i * width + line) as usize]
+        }
+    };
+    let mut out = vec![Color::BLACK; len as usize * lines as usize];
+    for line in 0..lines {
+        for j in 0..len {
+            // the position of the center of the new pixel in source pixels
+            let center = (j as Float + 0.5) * scale - 0.5;
+            let lo = (center - radius).ceil().max(0.0) as u32;
+            let hi = ((center + radius).floor() as u32).min(src_len - 1);
+            let (mut sum, mut total) = (Color::BLACK, 0.0);
+            for i in lo..=hi {
+                let w = 1.0 - (i as Float - center).abs() / radius;
+                if w > 0.0 {
+                    sum += at(line, i) * w;
+                    total += w;
+                }
+            }
+            let color = if total > 0.0 {
+                sum / total
+            } else {
+                let last = (src_len - 1) as Float;
+                at(line, center.round().clamp(0.0, last) as u32)
+            };
+            let k = if horizontal { line * len + j } else { j * width + line };
+            out[k as usize] = color;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::Color;
+    use crate::Float;
+    use super::Image;
+
+    /// Returns an image of width `width` and height `height` in which the red
+    /// channel of each pixel is its index, row by row from the top.
+    fn numbered(width: u32, height: u32) -> Image {
+        let pixels = (0..width * height)
+            .map(|i| Color { r: i as Float, g: 0.0, b: 1.0 })
+            .collect();
+        Image::from_pixels(width, height, pixels).unwrap()
+    }
+
+    /// Returns the red channels of the pixels of `image`.
+    fn reds(image: &Image) -> Vec<Float> {
+        image.pixels().map(|c| c.r).collect()
+    }
+
+    #[test]
+    fn crop() {
+        let image = numbered(4, 3);
+        let cropped = image.crop(1, 1, 2, 2).unwrap();
+        assert_eq!((cropped.width(), cropped.height()), (2, 2));
+        assert_eq!(reds(&cropped), [5.0, 6.0, 9.0, 10.0]);
+        assert_eq!(image.crop(0, 0, 4, 3), Some(image.clone()));
+    }
+
+    #[test]
+    fn crop_out_of_bounds() {
+        let image = numbered(4, 3);
+        assert_eq!(image.crop(3, 0, 2, 1), None);
+        assert_eq!(image.crop(0, 2, 1, 2), None);
+        assert_eq!(image.crop(u32::MAX, 0, 2, 1), None);
+    }
+
+    #[test]
+    fn crop_empty() {
+        let image = numbered(4, 3);
+        let crops = [(4, 0, 0, 3), (0, 3, 4, 0), (4, 3, 0, 0)];
+        for (x, y, width, height) in crops {
+            let cropped = image.crop(x, y, width, height).unwrap();
+            assert_eq!((cropped.width(), cropped.height()), (width, height));
+            assert_eq!(cropped.pixels().count(), 0);
+        }
+    }
+
+    #[test]
+    fn resize_same_size() {
+        let image = numbered(5, 3);
+        assert_eq!(reds(&image.resize(5, 3)), reds(&image));
+    }
+
+    #[test]
+    fn resize_constant() {
+        let color = Color { r: 0.25, g: 0.5, b: 2.0 };
+        let image = Image::from_pixels(7, 5, vec![color; 35]).unwrap();
+        for (width, height) in [(3, 2), (14, 10), (1, 1), (20, 3)] {
+            let resized = image.resize(width, height);
+            assert_eq!((resized.width(), resized.height()), (width, height));
+            assert!(resized.pixels().all(|c| {
+                let d = c - color;
+                d.r.abs().max(d.g.abs()).max(d.b.abs()) < 1.0e-6
+            }));
+        }
+    }
+
+    #[test]
+    fn resize_empty() {
+        let resized = Image::new(0, 0).resize(2, 3);
+        assert_eq!((resized.width(), resized.height()), (2, 3));
+        assert!(resized.pixels().all(|c| c == Color::BLACK));
+    }
+
+}
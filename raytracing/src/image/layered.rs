@@ -0,0 +1,99 @@
+use crate::Color;
+use crate::Float;
+use crate::image::Channel;
+use crate::image::Image;
+use crate::render::Aov;
+use crate::render::Aovs;
+
+/// An image along with a layer for each of a set of arbitrary output
+/// variables, all of which accumulate samples together.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayeredImage {
+
+    /// The colors of the scene.
+    pub beauty: Image,
+
+    /// The output variables of this image and their layers, each the same
+    /// size as `beauty`.
+    layers: Vec<(Aov, Image)>
+
+}
+
+impl LayeredImage {
+
+    /// Creates a black layered image of width `width` and height `height`,
+    /// with a layer for each of `aovs`.
+    pub fn new(width: u32, height: u32, aovs: &[Aov]) -> Self {
+        let mut layers: Vec<_> = aovs.iter()
+            .map(|&aov| (aov, Image::new(width, height)))
+            .collect();
+        layers.sort_by_key(|&(aov, _)| aov);
+        layers.dedup_by_key(|&mut (aov, _)| aov);
+        LayeredImage { beauty: Image::new(width, height), layers }
+    }
+
+    /// Returns the width of this image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.beauty.width()
+    }
+
+    /// Returns the height of this image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.beauty.height()
+    }
+
+    /// Returns the layer of this image for `aov`, or `None` if it has none.
+    pub fn layer(&self, aov: Aov) -> Option<&Image> {
+        self.layers.iter()
+            .find(|(a, _)| *a == aov)
+            .map(|(_, image)| image)
+    }
+
+    /// Returns the output variables of this image and their layers.
+    pub fn layers(&self) -> impl Iterator<Item = (Aov, &Image)> + '_ {
+        self.layers.iter().map(|(aov, image)| (*aov, image))
+    }
+
+    /// Adds a sample of `color` and `aovs` to the pixel at `(x, y)`, with
+    /// weight `weight`.
+    pub fn add_sample(
+        &mut self,
+        x: u32,
+        y: u32,
+        color: Color,
+        aovs: &Aovs,
+        weight: Float
+    ) {
+        self.beauty.add_sample(x, y, color, weight);
+        for (aov, image) in self.layers.iter_mut() {
+            image.add_sample(x, y, aov.value(aovs), weight);
+        }
+    }
+
+    /// Removes the samples from every pixel of every layer of this image.
+    pub fn clear(&mut self) {
+        self.beauty.clear();
+        for (_, image) in self.layers.iter_mut() {
+            image.clear();
+        }
+    }
+
+    /// Returns the channels of the output variables of this image, for
+    /// writing alongside the beauty image.
+    ///
+    /// Scalar variables are single channels named after the variable, while
+    /// the components of the others are grouped into a layer of that name.
+    pub fn channels(&self) -> Vec<Channel> {
+        self.layers.iter()
+            .flat_map(|(aov, image)| if aov.is_scalar() {
+                vec![Channel {
+                    name: aov.name().to_string(),
+                    values: image.pixels().map(|c| c.r).collect()
+                }]
+            } else {
+                Channel::split(aov.name(), image).to_vec()
+            })
+            .collect()
+    }
+
+}
@@ -0,0 +1,18 @@
+//! Contains `Image`, an owned buffer of linear colors which renderers can
+//! render into, `LayeredImage`, which adds layers of arbitrary output
+//! variables, and `AdaptiveImage`, which tracks the noise of its pixels, as
+//! well as functions to read and write images in high dynamic range formats.
+
+mod adaptive;
+mod channel;
+mod exr;
+mod image;
+mod layered;
+mod pfm;
+
+pub use adaptive::*;
+pub use channel::*;
+pub use exr::*;
+pub use image::*;
+pub use layered::*;
+pub use pfm::*;
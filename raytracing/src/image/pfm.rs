@@ -0,0 +1,188 @@
+use ::std::fs::File;
+use ::std::io::BufRead;
+use ::std::io::BufReader;
+use ::std::io::BufWriter;
+use ::std::io::Error;
+use ::std::io::ErrorKind;
+use ::std::io::Read;
+use ::std::io::Result;
+use ::std::io::Write;
+use ::std::path::Path;
+use crate::Color;
+use crate::Float;
+use crate::image::Image;
+
+/// Writes `image` to the color Portable Float Map file at `path`.
+///
+/// The values are stored as little-endian 32-bit floating-point numbers.
+// casting values to `f32` does nothing with the feature "f32"
+#[cfg_attr(feature = "f32", allow(clippy::unnecessary_cast))]
+pub fn write_pfm<P>(path: P, image: &Image) -> Result<()>
+    where P: AsRef<Path>
+{
+    let mut out = BufWriter::new(File::create(path)?);
+    // a negative scale indicates little-endian values
+    write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+    // rows are stored from the bottom up
+    for y in (0..image.height()).rev() {
+        for x in 0..image.width() {
+            let c = image.pixel(x, y);
+            for v in [c.r, c.g, c.b] {
+                out.write_all(&(v as f32).to_le_bytes())?;
+            }
+        }
+    }
+    out.flush()
+}
+
+/// Reads the color (`PF`) or grayscale (`Pf`) Portable Float Map file at
+/// `path`.
+pub fn read_pfm<P>(path: P) -> Result<Image>
+    where P: AsRef<Path>
+{
+    let mut input = BufReader::new(File::open(path)?);
+    let invalid = |msg: &str| {
+        Error::new(ErrorKind::InvalidData, msg.to_string())
+    };
+    // the header consists of four whitespace-separated tokens
+    let mut tokens = Vec::with_capacity(4);
+    while tokens.len() < 4 {
+        let mut token = Vec::new();
+        loop {
+            let byte = input.fill_buf()?
+                .first()
+                .copied()
+                .ok_or_else(|| invalid("truncated header"))?;
+            input.consume(1);
+            if byte.is_ascii_whitespace() {
+                if !token.is_empty() {
+                    break
+                }
+            } else {
+                token.push(byte);
+            }
+        }
+        tokens.push(String::from_utf8(token).map_err(|_| invalid("header"))?);
+    }
+    let channels = match tokens[0].as_str() {
+        "PF" => 3,
+        "Pf" => 1,
+        _ => return Err(invalid("not a portable float map"))
+    };
+    let width: u32 = tokens[1].parse().map_err(|_| invalid("width"))?;
+    let height: u32 = tokens[2].parse().map_err(|_| invalid("height"))?;
+    let scale: Float = tokens[3].parse().map_err(|_| invalid("scale"))?;
+    let little_endian = scale < 0.0;
+    let len = (width as usize).checked_mul(height as usize)
+        .ok_or_else(|| invalid("size"))?;
+    let size = len.checked_mul(4 * channels)
+        .ok_or_else(|| invalid("size"))?;
+    // memory grows as data arrives, rather than as the header claims
+    let mut bytes = Vec::new();
+    input.take(size as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != size {
+        return Err(invalid("truncated data"))
+    }
+    let values: Vec<Float> = bytes.chunks_exact(4)
+        .map(|b| {
+            let b = [b[0], b[1], b[2], b[3]];
+            let v = if little_endian {
+                f32::from_le_bytes(b)
+            } else {
+                f32::from_be_bytes(b)
+            };
+            v as Float
+        })
+        .collect();
+    let mut pixels = vec![Color::BLACK; len];
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        // rows are stored from the bottom up
+        let (x, y) = (i % width as usize, i / width as usize);
+        let j = channels * ((height as usize - 1 - y) * width as usize + x);
+        *pixel = if channels == 3 {
+            Color { r: values[j], g: values[j + 1], b: values[j + 2] }
+        } else {
+            Color { r: values[j], g: values[j], b: values[j] }
+        };
+    }
+    Image::from_pixels(width, height, pixels)
+        .ok_or_else(|| invalid("size mismatch"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ::std::env;
+    use ::std::fs;
+    use ::std::io::ErrorKind;
+    use ::std::path::PathBuf;
+    use ::std::process;
+    use crate::Color;
+    use crate::Float;
+    use crate::image::Image;
+    use super::read_pfm;
+    use super::write_pfm;
+
+    /// Returns a path for a temporary file named after `name`.
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("raytracing-{}-{name}", process::id()))
+    }
+
+    /// Returns the result of reading a PFM file consisting of `bytes`.
+    fn read_bytes(name: &str, bytes: &[u8]) -> ::std::io::Result<Image> {
+        let path = temp_path(name);
+        fs::write(&path, bytes).unwrap();
+        let image = read_pfm(&path);
+        fs::remove_file(&path).unwrap();
+        image
+    }
+
+    #[test]
+    fn round_trip() {
+        let pixels = (0..6)
+            .map(|i| {
+                let v = i as Float;
+                Color { r: v * 0.5, g: -v, b: 1.0e6 + v }
+            })
+            .collect();
+        let image = Image::from_pixels(3, 2, pixels).unwrap();
+        let path = temp_path("round_trip.pfm");
+        write_pfm(&path, &image).unwrap();
+        let read = read_pfm(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), image);
+    }
+
+    #[test]
+    fn grayscale_big_endian() {
+        // two rows, stored from the bottom up
+        let mut bytes = b"Pf\n2 2\n1.0\n".to_vec();
+        for v in [3.0f32, 4.0, 1.0, 2.0] {
+            bytes.extend(v.to_be_bytes());
+        }
+        let image = read_bytes("gray.pfm", &bytes).unwrap();
+        let values: Vec<Float> = image.pixels().map(|c| c.g).collect();
+        assert_eq!(values, [1.0, 2.0, 3.0, 4.0]);
+        assert!(image.pixels().all(|c| c.r == c.g && c.g == c.b));
+    }
+
+    #[test]
+    fn truncated_data() {
+        let error = read_bytes("truncated.pfm", b"PF\n100000 100000\n-1.0\n\0")
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn invalid_header() {
+        for (name, bytes) in [
+            ("magic.pfm", &b"P6\n1 1\n-1.0\n"[..]),
+            ("width.pfm", b"PF\n-1 1\n-1.0\n"),
+            ("header.pfm", b"PF\n1 1")
+        ] {
+            let error = read_bytes(name, bytes).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData, "{name}");
+        }
+    }
+
+}
@@ -1,12 +1,25 @@
 #![allow(clippy::module_inception)]
-#![feature(never_type)]
 
+pub mod denoise;
+pub mod image;
+pub mod material;
 pub mod render;
 pub mod scene;
 pub mod shape;
+pub mod texture;
 
 mod color;
+mod error;
+mod float;
+mod output;
+mod post;
 mod raster;
+mod tile;
 
 pub use color::*;
+pub use error::*;
+pub use float::*;
+pub use output::*;
+pub use post::*;
 pub use raster::*;
+pub use tile::*;
@@ -0,0 +1,126 @@
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Float;
+use crate::material::Ggx;
+use crate::material::Material;
+use crate::material::MaterialSample;
+use crate::material::fresnel_conductor;
+use crate::material::reflect;
+
+/// A rough metal, whose microfacets reflect light according to the Fresnel
+/// equations for its complex index of refraction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Conductor {
+
+    /// The real part of the index of refraction of this conductor, for red,
+    /// green and blue light.
+    pub eta: Color,
+
+    /// The imaginary part (the absorption coefficient) of the index of
+    /// refraction of this conductor, for red, green and blue light.
+    pub k: Color,
+
+    /// The distribution of the microfacets of this conductor.
+    pub distribution: Ggx
+
+}
+
+impl Conductor {
+
+    /// Creates a gold conductor with the microfacet distribution
+    /// `distribution`.
+    pub fn gold(distribution: Ggx) -> Self {
+        Conductor {
+            eta: Color { r: 0.143, g: 0.374, b: 1.442 },
+            k: Color { r: 3.983, g: 2.386, b: 1.603 },
+            distribution
+        }
+    }
+
+    /// Creates a copper conductor with the microfacet distribution
+    /// `distribution`.
+    pub fn copper(distribution: Ggx) -> Self {
+        Conductor {
+            eta: Color { r: 0.200, g: 0.924, b: 1.102 },
+            k: Color { r: 3.912, g: 2.452, b: 2.142 },
+            distribution
+        }
+    }
+
+    /// Creates an aluminium conductor with the microfacet distribution
+    /// `distribution`.
+    pub fn aluminium(distribution: Ggx) -> Self {
+        Conductor {
+            eta: Color { r: 1.657, g: 0.880, b: 0.521 },
+            k: Color { r: 9.224, g: 6.270, b: 4.837 },
+            distribution
+        }
+    }
+
+    /// Creates a silver conductor with the microfacet distribution
+    /// `distribution`.
+    pub fn silver(distribution: Ggx) -> Self {
+        Conductor {
+            eta: Color { r: 0.155, g: 0.117, b: 0.138 },
+            k: Color { r: 4.828, g: 3.122, b: 2.147 },
+            distribution
+        }
+    }
+
+}
+
+impl Material for Conductor {
+
+    /// Returns the value of the BSDF of this conductor for light arriving from
+    /// `wi` and leaving towards `wo`.
+    fn eval(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Color {
+        // conductors only reflect light on the outside
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Color::BLACK
+        }
+        let m = (wo + wi).normalize();
+        let f = fresnel_conductor(wi.dot(&m), self.eta, self.k);
+        let dist = &self.distribution;
+        f * (dist.d(m) * dist.g(wo, wi) / (4.0 * wo.z * wi.z))
+    }
+
+    /// Returns the probability density with which `sample` chooses `wi` given
+    /// `wo`.
+    fn pdf(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Float {
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return 0.0
+        }
+        let m = (wo + wi).normalize();
+        self.distribution.visible_d(wo, m) / (4.0 * wo.dot(&m))
+    }
+
+    /// Chooses a direction by reflecting `wo` across a visible microfacet.
+    fn sample(
+        &self,
+        wo: Vector3<Float>,
+        u: Vector3<Float>
+    ) -> Option<MaterialSample> {
+        if wo.z <= 0.0 {
+            return None
+        }
+        let dist = &self.distribution;
+        let m = dist.sample_visible_normal(wo, u.xy());
+        let wi = reflect(wo, m);
+        if wi.z <= 0.0 {
+            return None
+        }
+        let f = fresnel_conductor(wi.dot(&m), self.eta, self.k);
+        Some(MaterialSample {
+            wi,
+            // most of the terms cancel out with those of the pdf
+            weight: f * (dist.g(wo, wi) / dist.g1(wo)),
+            pdf: dist.visible_d(wo, m) / (4.0 * wo.dot(&m))
+        })
+    }
+
+    /// Returns the reflectance of this conductor at normal incidence.
+    fn albedo(&self) -> Color {
+        fresnel_conductor(1.0, self.eta, self.k)
+    }
+
+}
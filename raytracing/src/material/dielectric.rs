@@ -0,0 +1,157 @@
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Float;
+use crate::material::Ggx;
+use crate::material::Material;
+use crate::material::MaterialSample;
+use crate::material::fresnel_dielectric;
+use crate::material::reflect;
+use crate::material::refract;
+
+/// A rough dielectric such as frosted glass, whose microfacets both reflect
+/// and refract light, as described in Walter et al.'s "Microfacet Models for
+/// Refraction through Rough Surfaces" (2007).
+///
+/// The normal points out of the dielectric.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RoughDielectric {
+
+    /// The index of refraction of this dielectric, relative to the medium
+    /// outside of it.
+    pub ior: Float,
+
+    /// The color of the light transmitted through this dielectric.
+    pub tint: Color,
+
+    /// The distribution of the microfacets of this dielectric.
+    pub distribution: Ggx
+
+}
+
+impl RoughDielectric {
+
+    /// Returns the microfacet normal which scatters light from `wi` towards
+    /// `wo`, which lies in the upper hemisphere, along with the relative index
+    /// of refraction crossed by the light.
+    ///
+    /// Returns `None` if no microfacet facing both directions does so.
+    fn half_vector(
+        &self,
+        wo: Vector3<Float>,
+        wi: Vector3<Float>
+    ) -> Option<(Vector3<Float>, Float)> {
+        let reflected = wo.z * wi.z > 0.0;
+        let etap = if reflected {
+            1.0
+        } else if wo.z > 0.0 {
+            self.ior
+        } else {
+            1.0 / self.ior
+        };
+        let m = (wi * etap + wo).try_normalize(0.0)?;
+        let m = if m.z < 0.0 { -m } else { m };
+        // discard microfacets which face away from either direction
+        if m.dot(&wi) * wi.z <= 0.0 || m.dot(&wo) * wo.z <= 0.0 {
+            return None
+        }
+        Some((m, etap))
+    }
+
+}
+
+impl Material for RoughDielectric {
+
+    /// Returns the value of the BSDF of this dielectric for light arriving from
+    /// `wi` and leaving towards `wo`.
+    fn eval(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Color {
+        if wo.z == 0.0 || wi.z == 0.0 {
+            return Color::BLACK
+        }
+        let Some((m, etap)) = self.half_vector(wo, wi) else {
+            return Color::BLACK
+        };
+        let dist = &self.distribution;
+        let f = fresnel_dielectric(wo.dot(&m), self.ior);
+        let dg = dist.d(m) * dist.g(wo, wi);
+        if etap == 1.0 {
+            Color::WHITE * (f * dg / (4.0 * wo.z * wi.z).abs())
+        } else {
+            let denom = (wi.dot(&m) + wo.dot(&m) / etap).powi(2) * wi.z * wo.z;
+            let jacobian = (wi.dot(&m) * wo.dot(&m) / denom).abs();
+            // radiance is compressed into a smaller solid angle by refraction
+            self.tint * ((1.0 - f) * dg * jacobian / (etap * etap))
+        }
+    }
+
+    /// Returns the probability density with which `sample` chooses `wi` given
+    /// `wo`.
+    fn pdf(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Float {
+        if wo.z == 0.0 || wi.z == 0.0 {
+            return 0.0
+        }
+        let Some((m, etap)) = self.half_vector(wo, wi) else {
+            return 0.0
+        };
+        let r = fresnel_dielectric(wo.dot(&m), self.ior);
+        let visible_d = self.distribution.visible_d(wo, m);
+        if etap == 1.0 {
+            visible_d / (4.0 * wo.dot(&m).abs()) * r
+        } else {
+            /* the Jacobian of the mapping from microfacet normals to refracted
+             * directions */
+            let dm_dwi = wi.dot(&m).abs()
+                / (wi.dot(&m) + wo.dot(&m) / etap).powi(2);
+            visible_d * dm_dwi * (1.0 - r)
+        }
+    }
+
+    /// Chooses a direction by either reflecting `wo` across or refracting it
+    /// through a visible microfacet, with probabilities given by the Fresnel
+    /// equations.
+    fn sample(
+        &self,
+        wo: Vector3<Float>,
+        u: Vector3<Float>
+    ) -> Option<MaterialSample> {
+        if wo.z == 0.0 {
+            return None
+        }
+        let m = self.distribution.sample_visible_normal(wo, u.xy());
+        let r = fresnel_dielectric(wo.dot(&m), self.ior);
+        let wi = if u.z < r {
+            let wi = reflect(wo, m);
+            // the reflection must stay on the same side of the surface
+            if wi.z * wo.z <= 0.0 {
+                return None
+            }
+            wi
+        } else {
+            let (wi, _) = refract(wo, m, self.ior)?;
+            // the refraction must cross the surface
+            if wi.z * wo.z >= 0.0 {
+                return None
+            }
+            wi
+        };
+        let pdf = self.pdf(wo, wi);
+        if pdf <= 0.0 {
+            return None
+        }
+        Some(MaterialSample {
+            wi,
+            weight: self.eval(wo, wi) * (wi.z.abs() / pdf),
+            pdf
+        })
+    }
+
+    /// Returns the color of the light transmitted through this dielectric.
+    fn albedo(&self) -> Color {
+        self.tint
+    }
+
+    /// Returns `true`, since this dielectric refracts light through it.
+    fn transmits(&self) -> bool {
+        true
+    }
+
+}
@@ -0,0 +1,45 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+
+/// An orthonormal frame at a point on a surface, the axes of which are the
+/// tangent, the bitangent and the normal of the surface.
+///
+/// Materials are evaluated in this frame, so that the normal is the z-axis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frame {
+
+    /// The unit tangent, which is the x-axis of this frame.
+    pub s: Vector3<Float>,
+
+    /// The unit bitangent, which is the y-axis of this frame.
+    pub t: Vector3<Float>,
+
+    /// The unit normal, which is the z-axis of this frame.
+    pub n: Vector3<Float>
+
+}
+
+impl Frame {
+
+    /// Creates a right-handed frame from the normal `n` and the tangent
+    /// `tangent`, which is made perpendicular to `n`.
+    ///
+    /// The norm of `n` must be strictly positive, and `tangent` must not be
+    /// parallel to `n`.
+    pub fn new(n: Vector3<Float>, tangent: Vector3<Float>) -> Self {
+        let n = n.normalize();
+        let s = (tangent - tangent.dot(&n) * n).normalize();
+        Frame { s, t: n.cross(&s), n }
+    }
+
+    /// Returns `v`, given in world space, in the coordinates of this frame.
+    pub fn to_local(&self, v: Vector3<Float>) -> Vector3<Float> {
+        Vector3::new(v.dot(&self.s), v.dot(&self.t), v.dot(&self.n))
+    }
+
+    /// Returns `v`, given in the coordinates of this frame, in world space.
+    pub fn to_world(&self, v: Vector3<Float>) -> Vector3<Float> {
+        v.x * self.s + v.y * self.t + v.z * self.n
+    }
+
+}
@@ -0,0 +1,63 @@
+use crate::Color;
+use crate::Float;
+
+/// Returns the fraction of unpolarized light reflected by the boundary of a
+/// dielectric with relative index of refraction `eta`, for light arriving at
+/// an angle to the normal whose cosine is `cos_i`.
+///
+/// A negative `cos_i` indicates light arriving from inside the dielectric.
+pub fn fresnel_dielectric(cos_i: Float, eta: Float) -> Float {
+    let (cos_i, eta) = if cos_i < 0.0 {
+        (-cos_i, 1.0 / eta)
+    } else {
+        (cos_i.min(1.0), eta)
+    };
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    // total internal reflection
+    if sin2_t >= 1.0 {
+        return 1.0
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let r_parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (r_parallel * r_parallel + r_perpendicular * r_perpendicular)
+}
+
+/// Returns the fraction of unpolarized light of each color reflected by the
+/// surface of a conductor with complex index of refraction `eta + i k`, for
+/// light arriving at an angle to the normal whose cosine is `cos_i`.
+pub fn fresnel_conductor(cos_i: Float, eta: Color, k: Color) -> Color {
+    let channel = |eta: Float, k: Float| {
+        let cos2 = cos_i.clamp(0.0, 1.0).powi(2);
+        let sin2 = 1.0 - cos2;
+        let (eta2, k2) = (eta * eta, k * k);
+        let t0 = eta2 - k2 - sin2;
+        let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+        let t1 = a2_plus_b2 + cos2;
+        let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+        let t2 = 2.0 * cos_i.clamp(0.0, 1.0) * a;
+        let r_s = (t1 - t2) / (t1 + t2);
+        let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+        let t4 = t2 * sin2;
+        let r_p = r_s * (t3 - t4) / (t3 + t4);
+        0.5 * (r_p + r_s)
+    };
+    Color {
+        r: channel(eta.r, k.r),
+        g: channel(eta.g, k.g),
+        b: channel(eta.b, k.b)
+    }
+}
+
+/// Returns Schlick's approximation of the fraction of light of each color
+/// reflected by a surface whose reflectance at normal incidence is `f0`, for
+/// light arriving at an angle to the normal whose cosine is `cos_i`.
+pub fn fresnel_schlick(cos_i: Float, f0: Color) -> Color {
+    f0 + (Color::WHITE - f0) * schlick_weight(cos_i)
+}
+
+/// Returns the weight `(1 - cos_i)^5` given to the reflectance at grazing
+/// incidence by Schlick's approximation.
+pub fn schlick_weight(cos_i: Float) -> Float {
+    (1.0 - cos_i.clamp(0.0, 1.0)).powi(5)
+}
@@ -0,0 +1,119 @@
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Float;
+use crate::consts::PI;
+
+/// A direction in which light is scattered by a `Material`, chosen at random.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaterialSample {
+
+    /// The direction from which light arrives, in the local shading frame.
+    pub wi: Vector3<Float>,
+
+    /// The value of the BSDF for `wi`, multiplied by the absolute cosine of
+    /// the angle between `wi` and the normal, divided by `pdf`.
+    ///
+    /// This is the factor by which the light arriving from `wi` is weighted in
+    /// a Monte Carlo estimate of the light scattered.
+    pub weight: Color,
+
+    /// The probability density, with respect to solid angle, with which `wi`
+    /// was chosen.
+    pub pdf: Float
+
+}
+
+/// Implemented by models of how light scatters off of a surface, which
+/// describe the bidirectional scattering distribution function (BSDF) of that
+/// surface.
+///
+/// All directions are unit vectors in the local shading frame (see `Frame`),
+/// in which the normal is the z-axis and the tangent is the x-axis, and they
+/// point away from the surface.
+pub trait Material: Send + Sync {
+
+    /// Returns the value of the BSDF of the implementing material for light
+    /// arriving from `wi` and leaving towards `wo`.
+    fn eval(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Color;
+
+    /// Returns the probability density, with respect to solid angle, with
+    /// which `sample` chooses `wi` given `wo`.
+    fn pdf(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Float;
+
+    /// Chooses a direction from which light arrives to leave towards `wo`, with
+    /// a probability roughly proportional to its contribution.
+    ///
+    /// Each component of `u` must be a random number uniformly distributed
+    /// between 0.0 and 1.0. Returns `None` if no direction could be chosen.
+    fn sample(
+        &self,
+        wo: Vector3<Float>,
+        u: Vector3<Float>
+    ) -> Option<MaterialSample>;
+
+    /// Returns the approximate color of the implementing material in diffuse
+    /// white light, which guides denoising and is output as its albedo.
+    ///
+    /// By default, it is white.
+    fn albedo(&self) -> Color {
+        Color::WHITE
+    }
+
+    /// Returns whether the implementing material transmits light through its
+    /// surface, so that its two sides scatter light differently.
+    ///
+    /// Opaque materials only scatter light on the side of their normal, which
+    /// renderers turn towards the viewer. By default, they are opaque.
+    fn transmits(&self) -> bool {
+        false
+    }
+
+}
+
+/// Returns the reflection of `w` across `n`.
+pub fn reflect(w: Vector3<Float>, n: Vector3<Float>) -> Vector3<Float> {
+    2.0 * w.dot(&n) * n - w
+}
+
+/// Returns the refraction of `w` through a surface with normal `n`, and the
+/// relative index of refraction `eta` across it, along with the relative index
+/// of refraction actually crossed.
+///
+/// If `w` lies below the surface, the ratio is inverted. Returns `None` in the
+/// case of total internal reflection.
+pub fn refract(
+    w: Vector3<Float>,
+    n: Vector3<Float>,
+    eta: Float
+) -> Option<(Vector3<Float>, Float)> {
+    let (mut n, mut eta) = (n, eta);
+    let mut cos_i = n.dot(&w);
+    // if w arrives from the other side of the surface
+    if cos_i < 0.0 {
+        eta = 1.0 / eta;
+        cos_i = -cos_i;
+        n = -n;
+    }
+    // Snell's law
+    let sin2_i = (1.0 - cos_i * cos_i).max(0.0);
+    let sin2_t = sin2_i / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some((-w / eta + (cos_i / eta - cos_t) * n, eta))
+}
+
+/// Returns a direction in the upper hemisphere, chosen with a probability
+/// density proportional to the cosine of its angle to the z-axis, which is
+/// `w.z / PI`.
+///
+/// Each component of `u` must be a random number uniformly distributed
+/// between 0.0 and 1.0.
+pub fn sample_cosine_hemisphere(u: Vector2<Float>) -> Vector3<Float> {
+    // project a uniformly distributed point on the disk onto the hemisphere
+    let r = u.x.sqrt();
+    let phi = 2.0 * PI * u.y;
+    Vector3::new(r * phi.cos(), r * phi.sin(), (1.0 - u.x).max(0.0).sqrt())
+}
@@ -0,0 +1,127 @@
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::consts::PI;
+
+/// The smallest roughness of a `Ggx` distribution, below which its
+/// evaluation becomes numerically unstable.
+const MIN_ALPHA: Float = 1.0e-3;
+
+/// The GGX (Trowbridge-Reitz) distribution of microfacet normals, with the
+/// height-correlated Smith masking-shadowing function.
+///
+/// It may be anisotropic, with different roughnesses along the tangent and
+/// the bitangent, such as for brushed metal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ggx {
+
+    /// The roughness of this distribution along the tangent.
+    pub alpha_x: Float,
+
+    /// The roughness of this distribution along the bitangent.
+    pub alpha_y: Float
+
+}
+
+impl Ggx {
+
+    /// Creates a distribution with the perceptual roughnesses `roughness_x`
+    /// along the tangent and `roughness_y` along the bitangent, each between
+    /// 0.0 (smooth) and 1.0 (rough).
+    ///
+    /// Perceptual roughnesses are squared to obtain the roughnesses of the
+    /// distribution, which makes them vary more evenly in appearance.
+    pub fn new(roughness_x: Float, roughness_y: Float) -> Self {
+        Ggx {
+            alpha_x: (roughness_x * roughness_x).max(MIN_ALPHA),
+            alpha_y: (roughness_y * roughness_y).max(MIN_ALPHA)
+        }
+    }
+
+    /// Creates an isotropic distribution with the perceptual roughness
+    /// `roughness`, between 0.0 (smooth) and 1.0 (rough).
+    pub fn isotropic(roughness: Float) -> Self {
+        Ggx::new(roughness, roughness)
+    }
+
+    /// Returns the density of microfacets with the normal `m`, given in the
+    /// local shading frame.
+    pub fn d(&self, m: Vector3<Float>) -> Float {
+        if m.z <= 0.0 {
+            return 0.0
+        }
+        let e = (m.x / self.alpha_x).powi(2) + (m.y / self.alpha_y).powi(2)
+            + m.z * m.z;
+        1.0 / (PI * self.alpha_x * self.alpha_y * e * e)
+    }
+
+    /// Returns Smith's auxiliary function Λ for the direction `w`.
+    fn lambda(&self, w: Vector3<Float>) -> Float {
+        if w.z == 0.0 {
+            return Float::INFINITY
+        }
+        let a2_tan2 = ((self.alpha_x * w.x).powi(2)
+            + (self.alpha_y * w.y).powi(2)) / (w.z * w.z);
+        0.5 * ((1.0 + a2_tan2).sqrt() - 1.0)
+    }
+
+    /// Returns the fraction of microfacets visible from the direction `w`.
+    pub fn g1(&self, w: Vector3<Float>) -> Float {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Returns the fraction of microfacets visible from both `wo` and `wi`.
+    pub fn g(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Float {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Returns the density of microfacets with the normal `m` which are
+    /// visible from the direction `w`, which is the probability density with
+    /// which `sample_visible_normal` chooses `m`.
+    pub fn visible_d(&self, w: Vector3<Float>, m: Vector3<Float>) -> Float {
+        if w.z == 0.0 {
+            return 0.0
+        }
+        self.g1(w) / w.z.abs() * self.d(m) * w.dot(&m).abs()
+    }
+
+    /// Chooses the normal of a microfacet visible from the direction `w`, with
+    /// a probability proportional to its visible area, using the method of
+    /// Eric Heitz's "Sampling the GGX Distribution of Visible Normals" (2018).
+    ///
+    /// Each component of `u` must be a random number uniformly distributed
+    /// between 0.0 and 1.0. The normal always lies in the upper hemisphere.
+    pub fn sample_visible_normal(
+        &self,
+        w: Vector3<Float>,
+        u: Vector2<Float>
+    ) -> Vector3<Float> {
+        // stretch w into the configuration of a unit roughness
+        let mut wh = Vector3::new(self.alpha_x * w.x, self.alpha_y * w.y, w.z)
+            .normalize();
+        if wh.z < 0.0 {
+            wh = -wh;
+        }
+        // an orthonormal basis around wh
+        let len2 = wh.x * wh.x + wh.y * wh.y;
+        let t1 = if len2 > 0.0 {
+            Vector3::new(-wh.y, wh.x, 0.0) / len2.sqrt()
+        } else {
+            Vector3::x()
+        };
+        let t2 = wh.cross(&t1);
+        /* a uniformly distributed point on the disk, warped to the projection
+         * of the visible hemisphere */
+        let r = u.x.sqrt();
+        let phi = 2.0 * PI * u.y;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + wh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+        // project the point onto the hemisphere and unstretch it
+        let p3 = (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+        let nh = p1 * t1 + p2 * t2 + p3 * wh;
+        Vector3::new(self.alpha_x * nh.x, self.alpha_y * nh.y, nh.z.max(1.0e-6))
+            .normalize()
+    }
+
+}
@@ -0,0 +1,18 @@
+//! Contains `Material`, a trait implemented by models of how light scatters
+//! off of a surface, as well as various types that implement `Material`.
+
+mod conductor;
+mod dielectric;
+mod frame;
+mod fresnel;
+mod material;
+mod microfacet;
+mod principled;
+
+pub use conductor::*;
+pub use dielectric::*;
+pub use frame::*;
+pub use fresnel::*;
+pub use material::*;
+pub use microfacet::*;
+pub use principled::*;
@@ -0,0 +1,343 @@
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Float;
+use crate::consts::PI;
+use crate::material::Ggx;
+use crate::material::Material;
+use crate::material::MaterialSample;
+use crate::material::RoughDielectric;
+use crate::material::fresnel_schlick;
+use crate::material::reflect;
+use crate::material::sample_cosine_hemisphere;
+use crate::material::schlick_weight;
+
+/// A material described by the intuitive parameters of the Disney principled
+/// BSDF, as presented in Brent Burley's "Physically-Based Shading at Disney"
+/// (2012) and its extension to transmission (2015).
+///
+/// It combines a diffuse lobe with sheen and a subsurface approximation, a
+/// GGX specular lobe, a clearcoat lobe and a rough dielectric transmission
+/// lobe, each weighted by the parameters. All parameters other than
+/// `base_color` and `ior` lie between 0.0 and 1.0.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Principled {
+
+    /// The color of the diffuse reflection off of this material, or of its
+    /// specular reflection if it is metallic.
+    pub base_color: Color,
+
+    /// The degree to which this material is a metal rather than a dielectric.
+    pub metallic: Float,
+
+    /// The perceptual roughness of this material's specular reflection and
+    /// transmission, and the degree of retroreflection of its diffuse
+    /// reflection.
+    pub roughness: Float,
+
+    /// The degree to which the roughness along the bitangent exceeds that
+    /// along the tangent.
+    pub anisotropic: Float,
+
+    /// The strength of this material's specular reflection if it is a
+    /// dielectric, where 0.5 corresponds to a reflectance of 4% at normal
+    /// incidence.
+    pub specular: Float,
+
+    /// The degree to which this material's dielectric specular reflection is
+    /// tinted by its base color.
+    pub specular_tint: Float,
+
+    /// The strength of the retroreflective sheen at grazing angles, such as
+    /// that of cloth.
+    pub sheen: Float,
+
+    /// The degree to which the sheen is tinted by this material's base color.
+    pub sheen_tint: Float,
+
+    /// The strength of a second, transparent specular layer over this
+    /// material, such as lacquer.
+    pub clearcoat: Float,
+
+    /// The glossiness of the clearcoat layer.
+    pub clearcoat_gloss: Float,
+
+    /// The degree to which this material transmits light rather than
+    /// diffusely reflecting it, such as glass.
+    pub transmission: Float,
+
+    /// The index of refraction of this material's transmission lobe.
+    pub ior: Float,
+
+    /// The degree to which the diffuse reflection is flattened to approximate
+    /// the appearance of subsurface scattering, such as that of skin.
+    pub subsurface: Float
+
+}
+
+impl Default for Principled {
+
+    /// Returns a white, rough, non-metallic material with no sheen, clearcoat
+    /// or transmission.
+    fn default() -> Self {
+        Principled {
+            base_color: Color::WHITE * 0.8,
+            metallic: 0.0,
+            roughness: 0.5,
+            anisotropic: 0.0,
+            specular: 0.5,
+            specular_tint: 0.0,
+            sheen: 0.0,
+            sheen_tint: 0.5,
+            clearcoat: 0.0,
+            clearcoat_gloss: 1.0,
+            transmission: 0.0,
+            ior: 1.5,
+            subsurface: 0.0
+        }
+    }
+
+}
+
+/// The probabilities with which `Principled::sample` chooses each of the
+/// lobes, which sum to 1.0.
+struct LobeProbabilities {
+    diffuse: Float,
+    specular: Float,
+    clearcoat: Float,
+    transmission: Float
+}
+
+impl Principled {
+
+    /// Creates a material approximating a shape colored for Phong shading,
+    /// with the diffuse color `diffuse`, the specular color `specular` and the
+    /// shininess `shininess`.
+    pub fn from_phong(
+        diffuse: Color,
+        specular: Color,
+        shininess: Float
+    ) -> Self {
+        // the Beckmann-equivalent roughness of a Phong exponent
+        let alpha = (2.0 / (shininess.max(0.0) + 2.0)).sqrt();
+        Principled {
+            base_color: diffuse,
+            roughness: alpha.sqrt(),
+            specular: (specular.luminance() / 0.08).clamp(0.0, 1.0),
+            ..Principled::default()
+        }
+    }
+
+    /// Returns the base color normalized to unit luminance, which tints the
+    /// specular reflection and the sheen.
+    fn tint(&self) -> Color {
+        let lum = self.base_color.luminance();
+        if lum > 0.0 { self.base_color / lum } else { Color::WHITE }
+    }
+
+    /// Returns the reflectance of the specular lobe at normal incidence.
+    fn specular_f0(&self) -> Color {
+        let tint = Color::WHITE * (1.0 - self.specular_tint)
+            + self.tint() * self.specular_tint;
+        tint * (0.08 * self.specular) * (1.0 - self.metallic)
+            + self.base_color * self.metallic
+    }
+
+    /// Returns the distribution of the microfacets of the specular lobe.
+    fn specular_distribution(&self) -> Ggx {
+        let aspect = (1.0 - 0.9 * self.anisotropic).sqrt();
+        Ggx::new(self.roughness / aspect.sqrt(), self.roughness * aspect.sqrt())
+    }
+
+    /// Returns the roughness of the distribution of the microfacets of the
+    /// clearcoat lobe.
+    fn clearcoat_alpha(&self) -> Float {
+        0.1 * (1.0 - self.clearcoat_gloss) + 0.001 * self.clearcoat_gloss
+    }
+
+    /// Returns the transmission lobe.
+    fn dielectric(&self) -> RoughDielectric {
+        RoughDielectric {
+            ior: self.ior,
+            tint: self.base_color,
+            distribution: Ggx::isotropic(self.roughness)
+        }
+    }
+
+    /// Returns the weights of the diffuse and the transmission lobes.
+    fn weights(&self) -> (Float, Float) {
+        let dielectric = 1.0 - self.metallic;
+        (
+            dielectric * (1.0 - self.transmission),
+            dielectric * self.transmission
+        )
+    }
+
+    /// Returns the probabilities with which each lobe is sampled.
+    fn lobe_probabilities(&self) -> LobeProbabilities {
+        let (diffuse, transmission) = self.weights();
+        let specular = (1.0 - transmission)
+            * self.specular_f0().luminance().max(0.25);
+        let clearcoat = 0.25 * self.clearcoat;
+        let total = diffuse + specular + clearcoat + transmission;
+        LobeProbabilities {
+            diffuse: diffuse / total,
+            specular: specular / total,
+            clearcoat: clearcoat / total,
+            transmission: transmission / total
+        }
+    }
+
+    /// Returns the value of the diffuse and sheen lobes, unweighted.
+    fn eval_diffuse(
+        &self,
+        wo: Vector3<Float>,
+        wi: Vector3<Float>,
+        m: Vector3<Float>
+    ) -> Color {
+        let (fl, fv) = (schlick_weight(wi.z), schlick_weight(wo.z));
+        let cos_d = wi.dot(&m);
+        // the diffuse retroreflection
+        let fd90 = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+        let fd = (1.0 + (fd90 - 1.0) * fl) * (1.0 + (fd90 - 1.0) * fv);
+        // the Hanrahan-Krueger-like subsurface approximation
+        let fss90 = self.roughness * cos_d * cos_d;
+        let fss = (1.0 + (fss90 - 1.0) * fl) * (1.0 + (fss90 - 1.0) * fv);
+        let ss = 1.25 * (fss * (1.0 / (wi.z + wo.z) - 0.5) + 0.5);
+        let diffuse = self.base_color
+            * ((fd * (1.0 - self.subsurface) + ss * self.subsurface) / PI);
+        let sheen_color = Color::WHITE * (1.0 - self.sheen_tint)
+            + self.tint() * self.sheen_tint;
+        diffuse + sheen_color * (self.sheen * schlick_weight(cos_d))
+    }
+
+    /// Returns the density of the microfacets of the clearcoat layer with the
+    /// normal `m`, which follows the GTR1 distribution.
+    fn clearcoat_d(&self, m: Vector3<Float>) -> Float {
+        let a2 = self.clearcoat_alpha().powi(2);
+        (a2 - 1.0) / (PI * a2.ln() * (1.0 + (a2 - 1.0) * m.z * m.z))
+    }
+
+    /// Returns the value of the clearcoat lobe, unweighted.
+    fn eval_clearcoat(
+        &self,
+        wo: Vector3<Float>,
+        wi: Vector3<Float>,
+        m: Vector3<Float>
+    ) -> Float {
+        let f = 0.04 + 0.96 * schlick_weight(wi.dot(&m));
+        let g = Ggx { alpha_x: 0.25, alpha_y: 0.25 };
+        f * self.clearcoat_d(m) * g.g1(wo) * g.g1(wi) / (4.0 * wo.z * wi.z)
+    }
+
+    /// Chooses the normal of a clearcoat microfacet with a probability
+    /// density of `clearcoat_d(m) * m.z`.
+    fn sample_clearcoat_normal(&self, u: Vector2<Float>) -> Vector3<Float> {
+        let a2 = self.clearcoat_alpha().powi(2);
+        let cos_h = ((1.0 - a2.powf(1.0 - u.x)) / (1.0 - a2)).max(0.0).sqrt();
+        let sin_h = (1.0 - cos_h * cos_h).max(0.0).sqrt();
+        let phi = 2.0 * PI * u.y;
+        Vector3::new(sin_h * phi.cos(), sin_h * phi.sin(), cos_h)
+    }
+
+}
+
+impl Material for Principled {
+
+    /// Returns the value of the BSDF of this material for light arriving from
+    /// `wi` and leaving towards `wo`.
+    fn eval(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Color {
+        let (w_diffuse, w_transmission) = self.weights();
+        let mut f = if w_transmission > 0.0 {
+            self.dielectric().eval(wo, wi) * w_transmission
+        } else {
+            Color::BLACK
+        };
+        // the remaining lobes only reflect light on the outside
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return f
+        }
+        let m = (wo + wi).normalize();
+        if w_diffuse > 0.0 {
+            f += self.eval_diffuse(wo, wi, m) * w_diffuse;
+        }
+        let dist = self.specular_distribution();
+        f += fresnel_schlick(wi.dot(&m), self.specular_f0())
+            * (dist.d(m) * dist.g(wo, wi) / (4.0 * wo.z * wi.z)
+                * (1.0 - w_transmission));
+        if self.clearcoat > 0.0 {
+            f += Color::WHITE
+                * (0.25 * self.clearcoat * self.eval_clearcoat(wo, wi, m));
+        }
+        f
+    }
+
+    /// Returns the probability density with which `sample` chooses `wi` given
+    /// `wo`, which is the combination of the densities of each lobe.
+    fn pdf(&self, wo: Vector3<Float>, wi: Vector3<Float>) -> Float {
+        let probs = self.lobe_probabilities();
+        let mut pdf = if probs.transmission > 0.0 {
+            probs.transmission * self.dielectric().pdf(wo, wi)
+        } else {
+            0.0
+        };
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return pdf
+        }
+        let m = (wo + wi).normalize();
+        pdf += probs.diffuse * wi.z / PI;
+        pdf += probs.specular * self.specular_distribution().visible_d(wo, m)
+            / (4.0 * wo.dot(&m));
+        pdf += probs.clearcoat * self.clearcoat_d(m) * m.z
+            / (4.0 * wo.dot(&m));
+        pdf
+    }
+
+    /// Chooses one of the lobes of this material, then a direction according
+    /// to that lobe.
+    fn sample(
+        &self,
+        wo: Vector3<Float>,
+        u: Vector3<Float>
+    ) -> Option<MaterialSample> {
+        let probs = self.lobe_probabilities();
+        let mut uc = u.z;
+        let wi = if uc < probs.transmission {
+            // reuse the random number for the dielectric's own choice
+            uc /= probs.transmission;
+            self.dielectric().sample(wo, Vector3::new(u.x, u.y, uc))?.wi
+        } else if wo.z <= 0.0 {
+            return None
+        } else {
+            uc -= probs.transmission;
+            if uc < probs.diffuse {
+                sample_cosine_hemisphere(u.xy())
+            } else if uc < probs.diffuse + probs.specular {
+                let dist = self.specular_distribution();
+                reflect(wo, dist.sample_visible_normal(wo, u.xy()))
+            } else {
+                reflect(wo, self.sample_clearcoat_normal(u.xy()))
+            }
+        };
+        let pdf = self.pdf(wo, wi);
+        if pdf <= 0.0 || wi.z == 0.0 {
+            return None
+        }
+        Some(MaterialSample {
+            wi,
+            weight: self.eval(wo, wi) * (wi.z.abs() / pdf),
+            pdf
+        })
+    }
+
+    /// Returns the base color of this material.
+    fn albedo(&self) -> Color {
+        self.base_color
+    }
+
+    /// Returns whether this material has a transmission lobe.
+    fn transmits(&self) -> bool {
+        self.weights().1 > 0.0
+    }
+
+}
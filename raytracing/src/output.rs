@@ -0,0 +1,168 @@
+use crate::Color;
+use crate::Float;
+use crate::texture::splitmix64;
+
+/// An operator which compresses the unbounded range of linear colors into the
+/// range from 0.0 to 1.0 which can be displayed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ToneMap {
+
+    /// Clips each component to 1.0, leaving darker colors unchanged.
+    Clamp,
+
+    /// Erik Reinhard's operator `L / (1 + L)`, applied to the luminance `L` so
+    /// as to preserve hues, which never reaches white.
+    Reinhard,
+
+    /// Reinhard's extended operator, which maps the luminance `white` (and any
+    /// brighter) to white.
+    ExtendedReinhard { white: Float },
+
+    /// Krzysztof Narkowicz's fit of the ACES filmic reference rendering
+    /// transform, applied to each component.
+    AcesFilmic,
+
+    /// John Hable's filmic curve from Uncharted 2, applied to each component,
+    /// which maps the value `white` (and any brighter) to white.
+    Uncharted2 { white: Float }
+
+}
+
+impl ToneMap {
+
+    /// Returns `color`, tone mapped by this operator.
+    pub fn apply(&self, color: Color) -> Color {
+        let clamp = |c: Color| Color {
+            r: c.r.clamp(0.0, 1.0),
+            g: c.g.clamp(0.0, 1.0),
+            b: c.b.clamp(0.0, 1.0)
+        };
+        let per_component = |f: &dyn Fn(Float) -> Float| Color {
+            r: f(color.r.max(0.0)),
+            g: f(color.g.max(0.0)),
+            b: f(color.b.max(0.0))
+        };
+        match *self {
+            ToneMap::Clamp => clamp(color),
+            ToneMap::Reinhard => {
+                let l = color.luminance();
+                if l <= 0.0 {
+                    return Color::BLACK
+                }
+                clamp(color * (1.0 / (1.0 + l)))
+            },
+            ToneMap::ExtendedReinhard { white } => {
+                let l = color.luminance();
+                if l <= 0.0 {
+                    return Color::BLACK
+                }
+                let l_out = l * (1.0 + l / (white * white)) / (1.0 + l);
+                clamp(color * (l_out / l))
+            },
+            ToneMap::AcesFilmic => clamp(per_component(&|x| {
+                (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)
+            })),
+            ToneMap::Uncharted2 { white } => {
+                let curve = |x: Float| {
+                    // shoulder, linear, toe and angle strengths, and toe ratios
+                    let (a, b, c, d) = (0.15, 0.50, 0.10, 0.20);
+                    let (e, f) = (0.02, 0.30);
+                    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f))
+                        - e / f
+                };
+                let scale = 1.0 / curve(white);
+                clamp(per_component(&|x| curve(2.0 * x) * scale))
+            }
+        }
+    }
+
+}
+
+/// The transformation of linear colors into the 8-bit, sRGB-encoded pixels
+/// which are displayed or saved.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OutputTransform {
+
+    /// The exposure adjustment, in stops, applied before tone mapping.
+    ///
+    /// Each stop doubles the brightness of the output.
+    pub exposure: Float,
+
+    /// The tone mapping operator.
+    pub tone_map: ToneMap,
+
+    /// Whether to add a small amount of noise before quantizing, which hides
+    /// banding in smooth gradients.
+    pub dither: bool
+
+}
+
+impl Default for OutputTransform {
+
+    /// Returns the transform which clips colors and encodes them with the sRGB
+    /// transfer function, without any exposure adjustment or dithering.
+    fn default() -> Self {
+        OutputTransform {
+            exposure: 0.0,
+            tone_map: ToneMap::Clamp,
+            dither: false
+        }
+    }
+
+}
+
+impl OutputTransform {
+
+    /// Returns `color`, exposed, tone mapped and encoded with the sRGB transfer
+    /// function, such that each component lies between 0.0 and 1.0.
+    pub fn apply(&self, color: Color) -> Color {
+        let mapped = self.tone_map.apply(color * self.exposure.exp2());
+        Color {
+            r: srgb_encode(mapped.r),
+            g: srgb_encode(mapped.g),
+            b: srgb_encode(mapped.b)
+        }
+    }
+
+    /// Returns `color` as an opaque 8-bit RGBA pixel, for the pixel in column
+    /// `x` and row `y` of an image.
+    ///
+    /// The position of the pixel only determines the noise added by dithering.
+    pub fn encode(&self, color: Color, x: u32, y: u32) -> [u8; 4] {
+        let c = self.apply(color);
+        let noise = if self.dither {
+            // triangularly distributed noise of up to one quantization step
+            let mut state = ((x as u64) << 32) | y as u64;
+            let mut next = || (splitmix64(&mut state) >> 11) as Float
+                / (1u64 << 53) as Float;
+            next() + next() - 1.0
+        } else {
+            0.0
+        };
+        let quantize = |v: Float| {
+            (v * 255.0 + 0.5 + noise).clamp(0.0, 255.0) as u8
+        };
+        [quantize(c.r), quantize(c.g), quantize(c.b), 255]
+    }
+
+}
+
+/// Returns the linear component `v` encoded by the sRGB transfer function
+/// (opto-electronic transfer function).
+pub fn srgb_encode(v: Float) -> Float {
+    if v <= 0.0031308 {
+        12.92 * v
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Returns the sRGB-encoded component `v` decoded into linear light, which is
+/// the inverse of `srgb_encode`.
+pub fn srgb_decode(v: Float) -> Float {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
@@ -0,0 +1,234 @@
+use ::rayon::iter::IndexedParallelIterator;
+use ::rayon::iter::IntoParallelIterator;
+use ::rayon::iter::IntoParallelRefIterator;
+use ::rayon::iter::ParallelIterator;
+use crate::Color;
+use crate::Float;
+use crate::image::Image;
+use crate::texture::splitmix64;
+
+/// An effect applied to the linear colors of a rendered image before tone
+/// mapping, imitating the imperfections of real cameras and the adjustments of
+/// colorists.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PostEffect {
+
+    /// Adds a glow around highlights by blurring the light brighter than the
+    /// luminance `threshold` with a Gaussian of standard deviation `radius`,
+    /// in pixels, and adding it scaled by `intensity`.
+    Bloom { threshold: Float, intensity: Float, radius: Float },
+
+    /// Darkens the image towards its corners, where the brightness is scaled
+    /// by `1.0 - strength`.
+    Vignette { strength: Float },
+
+    /// Splits the color channels towards the edges of the image, as a lens
+    /// refracting wavelengths differently does, by scaling the red channel
+    /// outwards from the center by `1.0 + strength` and the blue channel
+    /// inwards by `1.0 - strength`.
+    ChromaticAberration { strength: Float },
+
+    /// Adds the monochrome noise of film grain, of which `strength` is the
+    /// greatest change in brightness relative to the color, and `seed`
+    /// chooses the pattern.
+    Grain { strength: Float, seed: u64 },
+
+    /// Sharpens edges by adding the difference between the image and its blur
+    /// scaled by `amount`, which is an unsharp mask.
+    Sharpen { amount: Float },
+
+    /// Grades colors by scaling them by `gain`, raising their darkest parts
+    /// by `lift`, applying the exponent `1.0 / gamma` and scaling their
+    /// saturation by `saturation`, which are neutral at black, white, white
+    /// and 1.0 respectively.
+    Grade { lift: Color, gamma: Color, gain: Color, saturation: Float }
+
+}
+
+impl PostEffect {
+
+    /// Returns `colors`, the pixels of an image of width `width` and height
+    /// `height` row by row from the top, with this effect applied.
+    fn apply(&self, colors: &[Color], width: u32, height: u32) -> Vec<Color> {
+        // the offset of a pixel from the center, in half diagonals
+        let half = (width as Float / 2.0, height as Float / 2.0);
+        let half_diagonal = half.0.hypot(half.1).max(Float::EPSILON);
+        let offset = |i: usize| {
+            let x = (i % width as usize) as Float + 0.5 - half.0;
+            let y = (i / width as usize) as Float + 0.5 - half.1;
+            (x / half_diagonal, y / half_diagonal)
+        };
+        match *self {
+            PostEffect::Bloom { threshold, intensity, radius } => {
+                let bright: Vec<Color> = colors.par_iter()
+                    .map(|&c| {
+                        let l = c.luminance();
+                        if l > threshold {
+                            c * ((l - threshold) / l)
+                        } else {
+                            Color::BLACK
+                        }
+                    })
+                    .collect();
+                let glow = gaussian_blur(&bright, width, height, radius);
+                colors.par_iter()
+                    .zip(glow)
+                    .map(|(&c, g)| c + g * intensity)
+                    .collect()
+            },
+            PostEffect::Vignette { strength } => {
+                colors.par_iter()
+                    .enumerate()
+                    .map(|(i, &c)| {
+                        let (x, y) = offset(i);
+                        c * (1.0 - strength * (x * x + y * y))
+                    })
+                    .collect()
+            },
+            PostEffect::ChromaticAberration { strength } => {
+                let sample = |x: Float, y: Float, scale: Float| bilinear(
+                    colors,
+                    width,
+                    height,
+                    half.0 + x * half_diagonal * scale,
+                    half.1 + y * half_diagonal * scale
+                );
+                (0..colors.len()).into_par_iter()
+                    .map(|i| {
+                        let (x, y) = offset(i);
+                        Color {
+                            r: sample(x, y, 1.0 / (1.0 + strength)).r,
+                            g: colors[i].g,
+                            b: sample(x, y, 1.0 / (1.0 - strength)).b
+                        }
+                    })
+                    .collect()
+            },
+            PostEffect::Grain { strength, seed } => {
+                colors.par_iter()
+                    .enumerate()
+                    .map(|(i, &c)| {
+                        // triangularly distributed noise between -1.0 and 1.0
+                        let mut state = seed ^ ((i as u64) << 16);
+                        let mut next = || {
+                            (splitmix64(&mut state) >> 11) as Float
+                                / (1u64 << 53) as Float
+                        };
+                        c * (1.0 + strength * (next() + next() - 1.0))
+                    })
+                    .collect()
+            },
+            PostEffect::Sharpen { amount } => {
+                let blurred = gaussian_blur(colors, width, height, 1.0);
+                colors.par_iter()
+                    .zip(blurred)
+                    .map(|(&c, b)| c + (c - b) * amount)
+                    .collect()
+            },
+            PostEffect::Grade { lift, gamma, gain, saturation } => {
+                let grade = |v: Float, lift: Float, gamma: Float, gain: Float| {
+                    let v = gain * v + lift * (1.0 - v);
+                    v.max(0.0).powf(1.0 / gamma)
+                };
+                colors.par_iter()
+                    .map(|&c| {
+                        let c = Color {
+                            r: grade(c.r, lift.r, gamma.r, gain.r),
+                            g: grade(c.g, lift.g, gamma.g, gain.g),
+                            b: grade(c.b, lift.b, gamma.b, gain.b)
+                        };
+                        let gray = Color::WHITE * c.luminance();
+                        gray + (c - gray) * saturation
+                    })
+                    .collect()
+            }
+        }
+    }
+
+}
+
+/// Returns `image` with `effects` applied to it in order.
+pub fn post_process(image: &Image, effects: &[PostEffect]) -> Image {
+    let (width, height) = (image.width(), image.height());
+    let colors = effects.iter()
+        .fold(image.pixels().collect(), |colors: Vec<Color>, effect| {
+            effect.apply(&colors, width, height)
+        });
+    Image::from_pixels(width, height, colors).unwrap()
+}
+
+/// Returns `colors`, the pixels of an image of width `width` and height
+/// `height` row by row from the top, blurred by a Gaussian of standard
+/// deviation `sigma`, in pixels, beyond the edges of which the nearest pixels
+/// are repeated.
+fn gaussian_blur(
+    colors: &[Color],
+    width: u32,
+    height: u32,
+    sigma: Float
+) -> Vec<Color> {
+    if sigma <= 0.0 || colors.is_empty() {
+        return colors.to_vec()
+    }
+    let radius = (3.0 * sigma).ceil() as i64;
+    let weights: Vec<Float> = (-radius..=radius)
+        .map(|d| (-(d * d) as Float / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let horizontal = blur_axis(colors, width, height, &weights, true);
+    blur_axis(&horizontal, width, height, &weights, false)
+}
+
+/// Returns `colors`, the pixels of an image of width `width` and height
+/// `height` row by row from the top, blurred horizontally or vertically by the
+/// kernel `weights`, which is centered on its middle weight.
+fn blur_axis(
+    colors: &[Color],
+    width: u32,
+    height: u32,
+    weights: &[Float],
+    horizontal: bool
+) -> Vec<Color> {
+    let total: Float = weights.iter().sum();
+    let radius = (weights.len() / 2) as i64;
+    let (w, h) = (width as i64, height as i64);
+    (0..colors.len()).into_par_iter()
+        .map(|i| {
+            let (x, y) = (i as i64 % w, i as i64 / w);
+            (-radius..=radius)
+                .zip(weights)
+                .map(|(d, &weight)| {
+                    let (x, y) = if horizontal {
+                        ((x + d).clamp(0, w - 1), y)
+                    } else {
+                        (x, (y + d).clamp(0, h - 1))
+                    };
+                    colors[(y * w + x) as usize] * weight
+                })
+                .sum::<Color>()
+                / total
+        })
+        .collect()
+}
+
+/// Returns the color of `colors`, the pixels of an image of width `width` and
+/// height `height` row by row from the top, at the point `(x, y)`, in pixels
+/// from the top-left corner, interpolated bilinearly between the centers of
+/// the nearest pixels.
+fn bilinear(
+    colors: &[Color],
+    width: u32,
+    height: u32,
+    x: Float,
+    y: Float
+) -> Color {
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (x - x0, y - y0);
+    let pixel = |px: Float, py: Float| {
+        let px = (px.max(0.0) as u32).min(width - 1);
+        let py = (py.max(0.0) as u32).min(height - 1);
+        colors[py as usize * width as usize + px as usize]
+    };
+    let row = |py: Float| pixel(x0, py) * (1.0 - tx) + pixel(x0 + 1.0, py) * tx;
+    row(y0) * (1.0 - ty) + row(y0 + 1.0) * ty
+}
@@ -1,16 +1,27 @@
+use ::nalgebra::Vector3;
+use ::rand::Rng;
 use ::rayon::iter::IndexedParallelIterator;
+use ::rayon::iter::IntoParallelIterator;
 use ::rayon::iter::ParallelIterator;
 use crate::Color;
+use crate::Float;
+use crate::image::AdaptiveImage;
+use crate::image::Image;
+use crate::image::LayeredImage;
+use crate::render::Aovs;
 use crate::render::Renderer;
+use crate::scene::Ray;
 
 /// Uses the algorithm `render` to rasterize the scene it references into the
 /// buffer `buf`, the width and height of which are `width` and `height`
 /// respectively.
+///
+/// Each pixel is the color along the ray through its center, the center of
+/// the camera's lens, at the time its shutter opens, or black if there is no
+/// such ray.
 pub fn rasterize_into<'a, R, I, C>(
     renderer: &R,
     buf: I,
-    fov_horiz: f64,
-    fov_vert: f64,
     width: u32,
     height: u32
 ) -> Result<(), R::CastError>
@@ -21,14 +32,167 @@ pub fn rasterize_into<'a, R, I, C>(
     buf.enumerate()
         .try_for_each(|(i, pixel)| {
         let i = i as u32;
-        let (width_f64, height_f64) = (width as f64, height as f64);
-        // the coordinates associated with this position in the buffer
-        let (x, y) = ((i % width) as f64, (i / height) as f64);
-        // the angles associated with those coordinates
-        let theta = (x - width_f64 / 2.0) * fov_horiz / width_f64;
-        let phi = -(y - height_f64 / 2.0) * fov_vert / height_f64;
+        // the coordinates of the center of this position in the buffer
+        let (x, y) = ((i % width) as Float + 0.5, (i / width) as Float + 0.5);
+        let ray = primary_ray(renderer, x, y, width, height, Vector3::zeros());
         // copy the calculated color into the buffer
-        *pixel = C::from(renderer.cast_ray(theta, phi)?);
+        *pixel = C::from(match ray {
+            Some(ray) => renderer.cast_ray(&ray)?,
+            None => Color::BLACK
+        });
         Ok(())
     })
 }
+
+/// Uses the algorithm `renderer` to render the scene it references, adding a
+/// sample of weight 1.0 to each pixel of `image` along the ray through its
+/// center, at a point on the camera's lens and a time chosen at random.
+///
+/// Rendering repeatedly into the same image with a stochastic renderer
+/// averages the samples, reducing noise.
+pub fn render_image<R>(
+    renderer: &R,
+    image: &mut Image
+) -> Result<(), R::CastError>
+    where R: Renderer + Sync
+{
+    let (width, height) = (image.width(), image.height());
+    image.samples_mut()
+        .enumerate()
+        .try_for_each_init(::rand::thread_rng, |rng, (i, (sum, weight))| {
+        let i = i as u32;
+        let (x, y) = ((i % width) as Float + 0.5, (i / width) as Float + 0.5);
+        let u = Vector3::from_fn(|_, _| rng.gen());
+        *sum += sample(renderer, x, y, width, height, u)?;
+        *weight += 1.0;
+        Ok(())
+    })
+}
+
+/// Uses the algorithm `renderer` to render the scene it references, adding a
+/// sample of weight 1.0 to each pixel of `image` along the ray through a point
+/// chosen at random within that pixel, as well as at a point on the camera's
+/// lens and a time chosen at random.
+///
+/// Rendering repeatedly into the same image averages samples spread over the
+/// area of each pixel, which both reduces noise and antialiases edges.
+pub fn render_image_jittered<R>(
+    renderer: &R,
+    image: &mut Image
+) -> Result<(), R::CastError>
+    where R: Renderer + Sync
+{
+    let (width, height) = (image.width(), image.height());
+    image.samples_mut()
+        .enumerate()
+        .try_for_each_init(::rand::thread_rng, |rng, (i, (sum, weight))| {
+        let i = i as u32;
+        // a random point within the pixel
+        let x = (i % width) as Float + rng.gen::<Float>();
+        let y = (i / width) as Float + rng.gen::<Float>();
+        let u = Vector3::from_fn(|_, _| rng.gen());
+        *sum += sample(renderer, x, y, width, height, u)?;
+        *weight += 1.0;
+        Ok(())
+    })
+}
+
+/// Uses the algorithm `renderer` to render the scene it references, adding a
+/// sample to each pixel of `image` which takes more samples along the ray
+/// through a point chosen at random within that pixel, as well as at a point
+/// on the camera's lens and a time chosen at random.
+///
+/// Rendering repeatedly into the same image spends samples only where noise
+/// remains visible, until no pixel takes more. Returns the number of pixels
+/// sampled, which is zero once that is the case.
+pub fn render_adaptive<R>(
+    renderer: &R,
+    image: &mut AdaptiveImage
+) -> Result<usize, R::CastError>
+    where R: Renderer + Sync
+{
+    let (width, height) = (image.width(), image.height());
+    let active: Vec<u32> = (0..width * height).into_par_iter()
+        .filter(|i| !image.is_converged(i % width, i / width))
+        .collect();
+    let samples: Vec<_> = active.into_par_iter()
+        .map_init(::rand::thread_rng, |rng, i| {
+        let x = (i % width) as Float + rng.gen::<Float>();
+        let y = (i / width) as Float + rng.gen::<Float>();
+        let u = Vector3::from_fn(|_, _| rng.gen());
+        sample(renderer, x, y, width, height, u).map(|color| (i, color))
+    })
+        .collect::<Result<_, _>>()?;
+    for &(i, color) in &samples {
+        image.add_sample(i % width, i / width, color);
+    }
+    Ok(samples.len())
+}
+
+/// Uses the algorithm `renderer` to render the scene it references, adding a
+/// sample of weight 1.0, along with its arbitrary output variables, to each
+/// pixel of `image` along the ray through its center, at a point on the
+/// camera's lens and a time chosen at random.
+pub fn render_layers<R>(
+    renderer: &R,
+    image: &mut LayeredImage
+) -> Result<(), R::CastError>
+    where R: Renderer + Sync
+{
+    let (width, height) = (image.width(), image.height());
+    let samples: Vec<_> = (0..width * height).into_par_iter()
+        .map_init(::rand::thread_rng, |rng, i| {
+        let (x, y) = ((i % width) as Float + 0.5, (i / width) as Float + 0.5);
+        let u = Vector3::from_fn(|_, _| rng.gen());
+        match primary_ray(renderer, x, y, width, height, u) {
+            Some(ray) => renderer.cast_ray_with_aovs(&ray),
+            None => Ok((Color::BLACK, Aovs::miss(Color::BLACK)))
+        }
+    })
+        .collect::<Result<_, _>>()?;
+    for (i, (color, aovs)) in samples.into_iter().enumerate() {
+        let i = i as u32;
+        image.add_sample(i % width, i / width, color, &aovs, 1.0);
+    }
+    Ok(())
+}
+
+/// Returns the ray entering the camera of `renderer` through the point
+/// `(x, y)`, in pixels from the top-left corner, of an image of width `width`
+/// and height `height`, or `None` if there is no such ray.
+///
+/// `u` chooses the point on the lens and the time of the ray.
+fn primary_ray<R>(
+    renderer: &R,
+    x: Float,
+    y: Float,
+    width: u32,
+    height: u32,
+    u: Vector3<Float>
+) -> Option<Ray>
+    where R: Renderer
+{
+    let (width, height) = (width as Float, height as Float);
+    renderer.camera().ray(x / width, y / height, width / height, u)
+}
+
+/// Returns the color along the ray entering the camera of `renderer` through
+/// the point `(x, y)`, in pixels from the top-left corner, of an image of
+/// width `width` and height `height`, or black if there is no such ray.
+///
+/// `u` chooses the point on the lens and the time of the ray.
+pub(crate) fn sample<R>(
+    renderer: &R,
+    x: Float,
+    y: Float,
+    width: u32,
+    height: u32,
+    u: Vector3<Float>
+) -> Result<Color, R::CastError>
+    where R: Renderer
+{
+    match primary_ray(renderer, x, y, width, height, u) {
+        Some(ray) => renderer.cast_ray(&ray),
+        None => Ok(Color::BLACK)
+    }
+}
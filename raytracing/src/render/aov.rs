@@ -0,0 +1,153 @@
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Float;
+
+/// An arbitrary output variable, a quantity other than the color of the
+/// scene which a renderer can output for each pixel, for compositing or
+/// debugging.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Aov {
+
+    /// The distance from the camera to the first surface visible, or infinity
+    /// if there is none.
+    Depth,
+
+    /// The unit normal, in world space, of the first surface visible.
+    Normal,
+
+    /// The color of the first surface visible in diffuse white light.
+    Albedo,
+
+    /// The index in the scene of the first shape visible, or -1.0 if there is
+    /// none.
+    ShapeIndex,
+
+    /// The texture coordinates of the first surface visible.
+    Uv,
+
+    /// The light reflected by the first surface visible directly from the
+    /// light sources, or the background if there is none.
+    Direct,
+
+    /// The light reflected by the first surface visible which does not come
+    /// directly from the light sources.
+    Indirect,
+
+    /// The fraction of the light sources hidden from the first surface
+    /// visible, which is 1.0 where it is entirely in shadow.
+    Shadow
+
+}
+
+impl Aov {
+
+    /// Every output variable, in the order of their declaration.
+    pub const ALL: [Aov; 8] = [
+        Aov::Depth,
+        Aov::Normal,
+        Aov::Albedo,
+        Aov::ShapeIndex,
+        Aov::Uv,
+        Aov::Direct,
+        Aov::Indirect,
+        Aov::Shadow
+    ];
+
+    /// Returns the name of this output variable, which names its layer in
+    /// output images.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::ShapeIndex => "shape",
+            Aov::Uv => "uv",
+            Aov::Direct => "direct",
+            Aov::Indirect => "indirect",
+            Aov::Shadow => "shadow"
+        }
+    }
+
+    /// Returns whether this output variable is a single number rather than a
+    /// color or a vector.
+    pub fn is_scalar(&self) -> bool {
+        matches!(self, Aov::Depth | Aov::ShapeIndex | Aov::Shadow)
+    }
+
+    /// Returns the value of this output variable in `aovs` as a color, whose
+    /// components are the components of vectors, or all equal to scalars.
+    pub fn value(&self, aovs: &Aovs) -> Color {
+        let vector = |v: Vector3<Float>| Color { r: v.x, g: v.y, b: v.z };
+        let scalar = |v: Float| Color { r: v, g: v, b: v };
+        match self {
+            Aov::Depth => scalar(aovs.depth),
+            Aov::Normal => vector(aovs.normal),
+            Aov::Albedo => aovs.albedo,
+            Aov::ShapeIndex => {
+                scalar(aovs.shape_index.map_or(-1.0, |i| i as Float))
+            },
+            Aov::Uv => Color { r: aovs.uv.x, g: aovs.uv.y, b: 0.0 },
+            Aov::Direct => aovs.direct,
+            Aov::Indirect => aovs.indirect,
+            Aov::Shadow => scalar(aovs.shadow)
+        }
+    }
+
+}
+
+/// The values of the arbitrary output variables for a single ray.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aovs {
+
+    /// The distance from the origin of the ray to the first surface it
+    /// intersects, or infinity if there is none.
+    pub depth: Float,
+
+    /// The unit normal, in world space, of the first surface intersected, or
+    /// zero if there is none.
+    pub normal: Vector3<Float>,
+
+    /// The color of the first surface intersected in diffuse white light, or
+    /// black if there is none.
+    pub albedo: Color,
+
+    /// The index in the scene of the first shape intersected, if any.
+    pub shape_index: Option<usize>,
+
+    /// The texture coordinates of the first surface intersected, or zero if
+    /// there is none.
+    pub uv: Vector2<Float>,
+
+    /// The light reflected by the first surface intersected directly from the
+    /// light sources, or the background if there is none.
+    pub direct: Color,
+
+    /// The light reflected by the first surface intersected which does not
+    /// come directly from the light sources.
+    pub indirect: Color,
+
+    /// The fraction of the light sources hidden from the first surface
+    /// intersected.
+    pub shadow: Float
+
+}
+
+impl Aovs {
+
+    /// Returns the output variables of a ray which intersects nothing and sees
+    /// the background color `background`.
+    pub fn miss(background: Color) -> Self {
+        Aovs {
+            depth: Float::INFINITY,
+            normal: Vector3::zeros(),
+            albedo: Color::BLACK,
+            shape_index: None,
+            uv: Vector2::zeros(),
+            direct: background,
+            indirect: Color::BLACK,
+            shadow: 0.0
+        }
+    }
+
+}
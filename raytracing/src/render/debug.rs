@@ -0,0 +1,169 @@
+use ::std::convert::Infallible;
+use crate::Color;
+use crate::Float;
+use crate::render::Aovs;
+use crate::render::Renderer;
+use crate::scene::Bvh;
+use crate::scene::Camera;
+use crate::scene::Ray;
+use crate::scene::Scene;
+use crate::shape::Shape;
+use crate::texture::splitmix64;
+
+/// The colors of a heatmap, from the fewest intersection tests to the most.
+const HEATMAP: [Color; 5] = [
+    Color { r: 0.0, g: 0.0, b: 0.5 },
+    Color { r: 0.0, g: 0.5, b: 1.0 },
+    Color { r: 0.0, g: 1.0, b: 0.0 },
+    Color { r: 1.0, g: 1.0, b: 0.0 },
+    Color { r: 1.0, g: 0.0, b: 0.0 }
+];
+
+/// A quantity which a `DebugRenderer` visualizes for the first surface
+/// visible along each ray, to reveal mistakes in the geometry of shapes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DebugView {
+
+    /// Colors surfaces by their unit normals, mapping each component from
+    /// -1.0 to 1.0 onto the corresponding channel from 0.0 to 1.0.
+    Normal,
+
+    /// Colors surfaces from white at the camera to black at the distance
+    /// `max_depth` and beyond.
+    Depth { max_depth: Float },
+
+    /// Colors surfaces by their texture coordinates, the first in the red
+    /// channel and the second in the green, wrapped to between 0.0 and 1.0.
+    ///
+    /// Triangles without texture coordinates of their own show their
+    /// barycentric coordinates.
+    Uv,
+
+    /// Colors each shape with an arbitrary color of its own, chosen by its
+    /// index in the scene.
+    ShapeIndex,
+
+    /// Colors pixels by the number of intersection tests against shapes and
+    /// bounding boxes their rays perform, from blue for none to red for
+    /// `max_tests` and more, whether or not they hit anything.
+    Heatmap { max_tests: u32 }
+
+}
+
+/// A renderer which visualizes a property of the surfaces of a scene rather
+/// than their lighting, with black for the background unless the property
+/// is of the ray itself.
+pub struct DebugRenderer<'scene, 'shape> {
+
+    /// The scene referenced by this renderer.
+    scene: &'scene Scene<'shape>,
+
+    /// The property which this renderer visualizes.
+    view: DebugView,
+
+    /// A bounding volume hierarchy over the shapes of the scene, covering
+    /// their motion while the shutter of its camera is open.
+    bvh: Bvh
+
+}
+
+impl<'scene, 'shape> DebugRenderer<'scene, 'shape> {
+
+    /// Creates a renderer which references `scene` and visualizes `view`.
+    pub fn new(scene: &'scene Scene<'shape>, view: DebugView) -> Self {
+        let bvh = Bvh::new(&scene.shapes, &scene.camera.shutter);
+        DebugRenderer { scene, view, bvh }
+    }
+
+}
+
+impl Renderer for DebugRenderer<'_, '_> {
+
+    type CastError = Infallible;
+
+    /// Returns the camera in the scene referenced by this renderer.
+    fn camera(&self) -> &Camera {
+        &self.scene.camera
+    }
+
+    /// Returns the color of the property visualized by this renderer along
+    /// `ray` in the scene it references.
+    fn cast_ray(&self, ray: &Ray) -> Result<Color, Self::CastError> {
+        self.cast_ray_with_aovs(ray).map(|(color, _)| color)
+    }
+
+    /// Returns the color of the property visualized by this renderer along
+    /// `ray` in the scene it references, along with the arbitrary output
+    /// variables of that ray.
+    ///
+    /// The color is the property visualized rather than any light, and is
+    /// output as the direct light, with no indirect light.
+    fn cast_ray_with_aovs(
+        &self,
+        ray: &Ray
+    ) -> Result<(Color, Aovs), Self::CastError> {
+        let shapes = &self.scene.shapes;
+        let (hit, tests) = self.bvh
+            .intersect_ray_counted(shapes, ray.origin, ray.dir, ray.time);
+        let heatmap = |max_tests: u32| {
+            heat(tests as Float / max_tests.max(1) as Float)
+        };
+        let Some((i, p)) = hit else {
+            let color = match self.view {
+                DebugView::Heatmap { max_tests } => heatmap(max_tests),
+                _ => Color::BLACK
+            };
+            return Ok((color, Aovs::miss(color)))
+        };
+        let s = self.scene.shape_at(i, ray.time);
+        let n = s.normal_at(p).normalize();
+        let uv = s.uv_at(p);
+        let depth = (p - ray.origin).norm();
+        let color = match self.view {
+            DebugView::Normal => Color {
+                r: 0.5 * (n.x + 1.0),
+                g: 0.5 * (n.y + 1.0),
+                b: 0.5 * (n.z + 1.0)
+            },
+            DebugView::Depth { max_depth } => {
+                Color::WHITE * (1.0 - depth / max_depth).clamp(0.0, 1.0)
+            },
+            DebugView::Uv => Color {
+                r: uv.x.rem_euclid(1.0),
+                g: uv.y.rem_euclid(1.0),
+                b: 0.0
+            },
+            DebugView::ShapeIndex => {
+                let mut state = i as u64;
+                let bits = splitmix64(&mut state);
+                let channel = |shift: u32| {
+                    // keep colors bright enough to tell apart from black
+                    0.25 + 0.75 * ((bits >> shift) & 0xff) as Float / 255.0
+                };
+                Color { r: channel(0), g: channel(8), b: channel(16) }
+            },
+            DebugView::Heatmap { max_tests } => heatmap(max_tests)
+        };
+        let aovs = Aovs {
+            depth,
+            normal: n,
+            albedo: s.diffuse_color_at(p),
+            shape_index: Some(i),
+            uv,
+            direct: color,
+            indirect: Color::BLACK,
+            shadow: 0.0
+        };
+        Ok((color, aovs))
+    }
+
+}
+
+/// Returns the color of a heatmap at `t`, from its coldest at 0.0 to its
+/// hottest at 1.0 and above.
+fn heat(t: Float) -> Color {
+    let x = t.clamp(0.0, 1.0) * (HEATMAP.len() - 1) as Float;
+    let i = (x.floor() as usize).min(HEATMAP.len() - 2);
+    let k = x - i as Float;
+    HEATMAP[i] * (1.0 - k) + HEATMAP[i + 1] * k
+}
@@ -1,8 +1,19 @@
 /// Contains `Renderer`, a trait implemented by algorithms which can render a
-/// `Scene`, as well as various types that implement `Renderer`.
+/// `Scene`, as well as various types that implement `Renderer` and a registry
+/// for choosing between them by name.
 
+mod aov;
+mod debug;
 mod naive;
+mod occlusion;
+mod path;
+mod registry;
 mod renderer;
 
+pub use aov::*;
+pub use debug::*;
 pub use naive::*;
+pub use occlusion::*;
+pub use path::*;
+pub use registry::*;
 pub use renderer::*;
@@ -1,10 +1,17 @@
-use ::nalgebra::Rotation3;
-use ::nalgebra::Unit;
-use ::nalgebra::Vector3;
 use ::rayon::iter::IntoParallelRefIterator;
 use ::rayon::iter::ParallelIterator;
 use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::SURFACE_OFFSET;
+use crate::render::AmbientOcclusion;
+use crate::render::Aovs;
 use crate::render::Renderer;
+use crate::render::validate_normal;
+use crate::render::validate_radiance;
+use crate::scene::Camera;
+use crate::scene::Light;
+use crate::scene::Ray;
 use crate::scene::Scene;
 use crate::shape::Shape;
 
@@ -12,7 +19,11 @@ use crate::shape::Shape;
 pub struct NaiveRenderer<'scene, 'shape> {
 
     /// The scene referenced by this renderer.
-    scene: &'scene Scene<'shape>
+    scene: &'scene Scene<'shape>,
+
+    /// The ambient occlusion which darkens the ambient light reflected by
+    /// surfaces, if any.
+    occlusion: Option<AmbientOcclusion>
 
 }
 
@@ -20,66 +31,63 @@ impl<'scene, 'shape> NaiveRenderer<'scene, 'shape> {
 
     /// Creates a renderer which references `scene`.
     pub fn new(scene: &'scene Scene<'shape>) -> Self {
-        NaiveRenderer { scene }
+        NaiveRenderer { scene, occlusion: None }
     }
 
-    /// Returns the first shape intersected by ray originating from `l0` in the
-    /// direction of `l`, as well as the point at which the intersection occurs.
-    ///
-    /// The norm of `l` must be strictly positive (i.e., nonzero).
-    fn intersect_ray(
-        &self,
-        l0: Vector3<f64>,
-        l: Vector3<f64>
-    ) -> Option<(&dyn Shape, Vector3<f64>)> {
-        self.scene.shapes.par_iter()
-            /* zip shapes with their intersection points and filter out the
-             * ones with no such point */
-            .filter_map(|s| s.intersect_ray(l0, l).map(|p| (s.as_ref(), p)))
-            // select the shape closest to the camera
-            .min_by(|(_, p1), (_, p2)| {
-                let d1 = (p1 - l0).norm();
-                let d2 = (p2 - l0).norm();
-                d1.partial_cmp(&d2).unwrap()
-            })
+    /// Creates a renderer which references `scene` and darkens the ambient
+    /// light reflected by surfaces by their ambient occlusion according to
+    /// `occlusion`.
+    pub fn with_ambient_occlusion(
+        scene: &'scene Scene<'shape>,
+        occlusion: AmbientOcclusion
+    ) -> Self {
+        NaiveRenderer { scene, occlusion: Some(occlusion) }
     }
 
 }
 
 impl Renderer for NaiveRenderer<'_, '_> {
 
-    type CastError = !;
+    type CastError = Error;
+
+    /// Returns the camera in the scene referenced by this renderer.
+    fn camera(&self) -> &Camera {
+        &self.scene.camera
+    }
 
-    /// Returns the color visible in the scene referenced by this renderer,
-    /// from the camera in that scene, in the direction (relative to the
-    /// camera) given by `theta` and `phi`.
-    fn cast_ray(
+    /// Returns the color visible in the scene referenced by this renderer
+    /// along `ray`.
+    ///
+    /// Returns an error if the normal of the surface visible is zero or not
+    /// finite, or the color is not finite.
+    fn cast_ray(&self, ray: &Ray) -> Result<Color, Self::CastError> {
+        self.cast_ray_with_aovs(ray).map(|(color, _)| color)
+    }
+
+    /// Returns the color visible in the scene referenced by this renderer
+    /// along `ray`, along with the arbitrary output variables of that ray.
+    ///
+    /// The ambient light reflected by the surface is its indirect light.
+    fn cast_ray_with_aovs(
         &self,
-        theta: f64,
-        phi: f64
-    ) -> Result<Color, Self::CastError> {
-        // abbreviations
-        let camera = &self.scene.camera;
-        // rotate the default camera angle by theta leftwards, then phi upwards
-        let forward = {
-            let unit_up = Unit::new_normalize(camera.up);
-            let unit_left = Unit::new_normalize(camera.left());
-            Rotation3::from_axis_angle(&unit_up, theta)
-                * Rotation3::from_axis_angle(&unit_left, phi)
-                * camera.forward
-        };
+        ray: &Ray
+    ) -> Result<(Color, Aovs), Self::CastError> {
         // if the camera's ray intersects a shape in the scene
-        if let Some((s, p)) = self.intersect_ray(camera.pos, forward) {
+        let hit = self.scene
+            .intersect_ray_index_at(ray.origin, ray.dir, ray.time);
+        if let Some((i, p)) = hit {
+            let s = self.scene.shape_at(i, ray.time);
             // a vector normal to the shape at the intersection point
             let n = s.normal_at(p);
+            validate_normal(n, i, p)?;
             let n_norm = n.norm();
             // the displacement from p to the camera
-            let dc = camera.pos - p;
+            let dc = ray.origin - p;
             let dc_norm = dc.norm();
             // the shininess of the shape at the intersection point
             let h = s.shininess_at(p);
 
-            let lights = self.scene.lights.par_iter()
+            let lights: Vec<&Light> = self.scene.lights.par_iter()
                 /* filter out the lights such that a ray from p to it is
                  * intersected by a shape in front of it */
                 .filter(|light| {
@@ -87,12 +95,31 @@ impl Renderer for NaiveRenderer<'_, '_> {
                     let dl = light.pos - p;
                     /* returns true if the ray has no intersection point or if
                      * the point is farther from p than the light source */
-                    self.intersect_ray(p + dl * 1.0e-12, dl)
-                        .map_or(true, |(_, q)| (p - q).norm() > dl.norm())
-                });
-            let tot_ambient = self.scene.ambient_color * s.ambient_color_at(p);
+                    let offset = SURFACE_OFFSET * p.amax().max(1.0);
+                    let l0 = p + dl.normalize() * offset;
+                    self.scene
+                        .intersect_ray_index_at(l0, dl, ray.time)
+                        .is_none_or(|(_, q)| (p - q).norm() > dl.norm())
+                })
+                .collect();
+            let mut tot_ambient =
+                self.scene.ambient_color * s.ambient_color_at(p);
+            if let Some(occlusion) = &self.occlusion {
+                // the normal on the side of the shape facing the camera
+                let n = if n.dot(&dc) < 0.0 { -n } else { n };
+                tot_ambient *= occlusion.visibility(
+                    p,
+                    n,
+                    s.tangent_at(p),
+                    |l0, l| {
+                        self.scene.intersect_ray_index_at(l0, l, ray.time)
+                            .map(|(_, q)| q)
+                    },
+                    &mut ::rand::thread_rng()
+                );
+            }
             // the incoming light to be reflected
-            let (sum_diffuse, sum_specular) = lights.map(|light| {
+            let (sum_diffuse, sum_specular) = lights.par_iter().map(|light| {
                 // the displacement from p to the light source
                 let dl = light.pos - p;
                 // the diffuse reflection from the light source
@@ -120,10 +147,28 @@ impl Renderer for NaiveRenderer<'_, '_> {
             );
             let tot_diffuse = s.diffuse_color_at(p) * sum_diffuse;
             let tot_specular = s.specular_color_at(p) * sum_specular;
-            Ok(tot_ambient + tot_diffuse + tot_specular)
+            let num_lights = self.scene.lights.len();
+            let aovs = Aovs {
+                depth: dc_norm,
+                normal: n / n_norm,
+                albedo: s.diffuse_color_at(p),
+                shape_index: Some(i),
+                uv: s.uv_at(p),
+                direct: tot_diffuse + tot_specular,
+                indirect: tot_ambient,
+                shadow: if num_lights > 0 {
+                    1.0 - lights.len() as Float / num_lights as Float
+                } else {
+                    0.0
+                }
+            };
+            let color = tot_ambient + tot_diffuse + tot_specular;
+            validate_radiance(color, Some(i))?;
+            Ok((color, aovs))
         } else {
             // if no shape is intersected, return the scene's background color
-            Ok(self.scene.background_color)
+            let background = self.scene.background_color;
+            Ok((background, Aovs::miss(background)))
         }
     }
 
@@ -0,0 +1,164 @@
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use ::rand::Rng;
+use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::SURFACE_OFFSET;
+use crate::material::Frame;
+use crate::material::sample_cosine_hemisphere;
+use crate::render::Aovs;
+use crate::render::Renderer;
+use crate::render::validate_normal;
+use crate::scene::Bvh;
+use crate::scene::Camera;
+use crate::scene::Ray;
+use crate::scene::Scene;
+use crate::shape::Shape;
+
+/// The settings of ambient occlusion, the fraction of the ambient light
+/// surrounding a point on a surface which is not blocked by nearby surfaces,
+/// which darkens crevices and corners.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AmbientOcclusion {
+
+    /// The number of rays cast from each point to estimate its occlusion.
+    pub samples: u32,
+
+    /// The distance beyond which surfaces do not occlude a point.
+    pub max_distance: Float
+
+}
+
+impl AmbientOcclusion {
+
+    /// Returns a random estimate of the fraction of the ambient light which
+    /// reaches `p`, on a surface with normal `n` and tangent `tangent`, by
+    /// casting rays in directions chosen with a probability proportional to
+    /// the cosine of their angle to `n`.
+    ///
+    /// `intersect` returns the point at which a ray originating from its first
+    /// argument in the direction of its second intersects the scene, if any.
+    pub fn visibility<F, R>(
+        &self,
+        p: Vector3<Float>,
+        n: Vector3<Float>,
+        tangent: Vector3<Float>,
+        intersect: F,
+        rng: &mut R
+    ) -> Float
+        where F: Fn(Vector3<Float>, Vector3<Float>) -> Option<Vector3<Float>>,
+              R: Rng
+    {
+        if self.samples == 0 {
+            return 1.0
+        }
+        let frame = Frame::new(n, tangent);
+        let origin = p + frame.n * SURFACE_OFFSET * p.amax().max(1.0);
+        let visible = (0..self.samples)
+            .filter(|_| {
+                let u = Vector2::new(rng.gen(), rng.gen());
+                let dir = frame.to_world(sample_cosine_hemisphere(u));
+                intersect(origin, dir)
+                    .is_none_or(|q| (q - p).norm() > self.max_distance)
+            })
+            .count();
+        visible as Float / self.samples as Float
+    }
+
+}
+
+/// A renderer which shows only the ambient occlusion of the surfaces of a
+/// scene, from black where they are entirely occluded to white where they are
+/// not occluded at all, which is also the color of the background.
+pub struct AmbientOcclusionRenderer<'scene, 'shape> {
+
+    /// The scene referenced by this renderer.
+    scene: &'scene Scene<'shape>,
+
+    /// The settings of the ambient occlusion rendered.
+    occlusion: AmbientOcclusion,
+
+    /// A bounding volume hierarchy over the shapes of the scene, covering
+    /// their motion while the shutter of its camera is open.
+    bvh: Bvh
+
+}
+
+impl<'scene, 'shape> AmbientOcclusionRenderer<'scene, 'shape> {
+
+    /// Creates a renderer which references `scene` and renders its ambient
+    /// occlusion according to `occlusion`.
+    pub fn new(
+        scene: &'scene Scene<'shape>,
+        occlusion: AmbientOcclusion
+    ) -> Self {
+        let bvh = Bvh::new(&scene.shapes, &scene.camera.shutter);
+        AmbientOcclusionRenderer { scene, occlusion, bvh }
+    }
+
+}
+
+impl Renderer for AmbientOcclusionRenderer<'_, '_> {
+
+    type CastError = Error;
+
+    /// Returns the camera in the scene referenced by this renderer.
+    fn camera(&self) -> &Camera {
+        &self.scene.camera
+    }
+
+    /// Returns a random estimate of the ambient occlusion of the first surface
+    /// visible along `ray` in the scene referenced by this renderer.
+    ///
+    /// Averaging many estimates for the same ray reduces noise. Returns an
+    /// error if the normal of the surface visible is zero or not finite.
+    fn cast_ray(&self, ray: &Ray) -> Result<Color, Self::CastError> {
+        self.cast_ray_with_aovs(ray).map(|(color, _)| color)
+    }
+
+    /// Returns a random estimate of the ambient occlusion of the first surface
+    /// visible along `ray` in the scene referenced by this renderer, along
+    /// with the arbitrary output variables of that ray.
+    ///
+    /// The occlusion is the indirect light of the surface.
+    fn cast_ray_with_aovs(
+        &self,
+        ray: &Ray
+    ) -> Result<(Color, Aovs), Self::CastError> {
+        let shapes = &self.scene.shapes;
+        let hit = self.bvh.intersect_ray(shapes, ray.origin, ray.dir, ray.time);
+        let Some((i, p)) = hit else {
+            return Ok((Color::WHITE, Aovs::miss(Color::WHITE)))
+        };
+        let s = self.scene.shape_at(i, ray.time);
+        // the normal on the side of the surface facing the ray
+        let n = s.normal_at(p);
+        validate_normal(n, i, p)?;
+        let n = n.normalize();
+        let n = if n.dot(&ray.dir) > 0.0 { -n } else { n };
+        let visibility = self.occlusion.visibility(
+            p,
+            n,
+            s.tangent_at(p),
+            |l0, l| {
+                self.bvh.intersect_ray(shapes, l0, l, ray.time)
+                    .map(|(_, q)| q)
+            },
+            &mut ::rand::thread_rng()
+        );
+        let color = Color::WHITE * visibility;
+        let aovs = Aovs {
+            depth: (p - ray.origin).norm(),
+            normal: n,
+            albedo: s.diffuse_color_at(p),
+            shape_index: Some(i),
+            uv: s.uv_at(p),
+            direct: Color::BLACK,
+            indirect: color,
+            shadow: 0.0
+        };
+        Ok((color, aovs))
+    }
+
+}
@@ -0,0 +1,229 @@
+use ::nalgebra::Vector3;
+use ::rand::Rng;
+use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::SURFACE_OFFSET;
+use crate::material::Frame;
+use crate::material::Material;
+use crate::material::Principled;
+use crate::render::Aovs;
+use crate::render::Renderer;
+use crate::render::validate_normal;
+use crate::render::validate_radiance;
+use crate::scene::Bvh;
+use crate::scene::Camera;
+use crate::scene::Ray;
+use crate::scene::Scene;
+use crate::shape::Shape;
+
+/// The number of bounces after which paths may be terminated at random.
+const MIN_BOUNCES: u32 = 3;
+
+/// A unidirectional path tracer, which estimates the light arriving at the
+/// camera by following random paths of light backwards through the scene.
+///
+/// Shapes scatter light according to their materials, or according to a
+/// `Principled` material approximating their colors and shininess if they
+/// have none. Each light source is a point light whose intensity is its
+/// diffuse color and whose light falls off with the square of the distance,
+/// and the background is a uniform source of light surrounding the scene.
+pub struct PathRenderer<'scene, 'shape> {
+
+    /// The scene referenced by this renderer.
+    scene: &'scene Scene<'shape>,
+
+    /// The maximum number of times a path may bounce off of a surface.
+    max_bounces: u32,
+
+    /// A bounding volume hierarchy over the shapes of the scene, covering
+    /// their motion while the shutter of its camera is open.
+    bvh: Bvh
+
+}
+
+impl<'scene, 'shape> PathRenderer<'scene, 'shape> {
+
+    /// Creates a renderer which references `scene` and follows paths for at
+    /// most `max_bounces` bounces.
+    pub fn new(scene: &'scene Scene<'shape>, max_bounces: u32) -> Self {
+        let bvh = Bvh::new(&scene.shapes, &scene.camera.shutter);
+        PathRenderer { scene, max_bounces, bvh }
+    }
+
+    /// Returns `p` offset from the surface with the normal `n`, towards the
+    /// side of it into which `w` points.
+    fn offset(
+        p: Vector3<Float>,
+        n: Vector3<Float>,
+        w: Vector3<Float>
+    ) -> Vector3<Float> {
+        let scale = SURFACE_OFFSET * p.amax().max(1.0);
+        if n.dot(&w) >= 0.0 { p + scale * n } else { p - scale * n }
+    }
+
+    /// Returns the index of the first shape intersected at `time` by a ray
+    /// originating from `l0` in the direction of `l`, as well as the point at
+    /// which the intersection occurs.
+    fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>,
+        time: Float
+    ) -> Option<(usize, Vector3<Float>)> {
+        self.bvh.intersect_ray(&self.scene.shapes, l0, l, time)
+    }
+
+    /// Returns the light arriving from the light sources of the scene at `p`
+    /// at `time` and scattered by `material` towards `wo`, as well as the
+    /// number of light sources hidden from `p`.
+    fn direct_light(
+        &self,
+        material: &dyn Material,
+        frame: &Frame,
+        p: Vector3<Float>,
+        wo: Vector3<Float>,
+        time: Float
+    ) -> (Color, usize) {
+        let mut hidden = 0;
+        let light = self.scene.lights.iter()
+            .map(|light| {
+                // the displacement from p to the light source
+                let dl = light.pos - p;
+                let dist = dl.norm();
+                let wi = dl / dist;
+                // the light source is visible if nothing lies in front of it
+                let visible = self
+                    .intersect_ray(Self::offset(p, frame.n, wi), wi, time)
+                    .is_none_or(|(_, q)| (q - p).norm() > dist);
+                if !visible {
+                    hidden += 1;
+                    return Color::BLACK
+                }
+                let wi = frame.to_local(wi);
+                material.eval(wo, wi) * light.diffuse_color
+                    * (wi.z.abs() / (dist * dist))
+            })
+            .sum();
+        (light, hidden)
+    }
+
+}
+
+impl Renderer for PathRenderer<'_, '_> {
+
+    type CastError = Error;
+
+    /// Returns the camera in the scene referenced by this renderer.
+    fn camera(&self) -> &Camera {
+        &self.scene.camera
+    }
+
+    /// Returns a random estimate of the light arriving along `ray` in the
+    /// scene referenced by this renderer.
+    ///
+    /// Averaging many estimates for the same ray reduces noise. Returns an
+    /// error if the normal of a surface along the path is zero or not finite,
+    /// or the estimate is not finite.
+    fn cast_ray(&self, ray: &Ray) -> Result<Color, Self::CastError> {
+        self.cast_ray_with_aovs(ray).map(|(color, _)| color)
+    }
+
+    /// Returns a random estimate of the light arriving along `ray` in the
+    /// scene referenced by this renderer, along with the arbitrary output
+    /// variables of that ray.
+    ///
+    /// Light which reaches the first surface after bouncing off of another is
+    /// its indirect light.
+    fn cast_ray_with_aovs(
+        &self,
+        ray: &Ray
+    ) -> Result<(Color, Aovs), Self::CastError> {
+        let mut rng = ::rand::thread_rng();
+        let (mut origin, mut dir) = (ray.origin, ray.dir);
+        let mut aovs = Aovs::miss(self.scene.background_color);
+        // the light gathered so far along the path
+        let mut radiance = Color::BLACK;
+        // the fraction of light scattered along the path so far
+        let mut throughput = Color::WHITE;
+        for bounce in 0..=self.max_bounces {
+            let hit = self.intersect_ray(origin, dir, ray.time);
+            let Some((i, p)) = hit else {
+                // the path escapes the scene
+                radiance += throughput * self.scene.background_color;
+                break
+            };
+            let s = self.scene.shape_at(i, ray.time);
+            let n = s.normal_at(p);
+            validate_normal(n, i, p)?;
+            let fallback;
+            let material: &dyn Material = match s.material_at(p) {
+                Some(material) => material,
+                None => {
+                    fallback = Principled::from_phong(
+                        s.diffuse_color_at(p),
+                        s.specular_color_at(p),
+                        s.shininess_at(p)
+                    );
+                    &fallback
+                }
+            };
+            let wo_world = -dir.normalize();
+            /* opaque materials are lit on the side facing the viewer, whereas
+             * transmissive ones tell the inside of a surface from the
+             * outside */
+            let n = if !material.transmits() && n.dot(&wo_world) < 0.0 {
+                -n
+            } else {
+                n
+            };
+            let frame = Frame::new(n, s.tangent_at(p));
+            let wo = frame.to_local(wo_world);
+            let (direct, hidden) =
+                self.direct_light(material, &frame, p, wo, ray.time);
+            radiance += throughput * direct;
+            if bounce == 0 {
+                let num_lights = self.scene.lights.len();
+                aovs = Aovs {
+                    depth: (p - origin).norm(),
+                    normal: frame.n,
+                    albedo: material.albedo(),
+                    shape_index: Some(i),
+                    uv: s.uv_at(p),
+                    direct,
+                    indirect: Color::BLACK,
+                    shadow: if num_lights > 0 {
+                        hidden as Float / num_lights as Float
+                    } else {
+                        0.0
+                    }
+                };
+            }
+            if bounce == self.max_bounces {
+                break
+            }
+            // continue the path in a direction chosen by the material
+            let u = Vector3::new(rng.gen(), rng.gen(), rng.gen());
+            let Some(sample) = material.sample(wo, u) else {
+                break
+            };
+            throughput *= sample.weight;
+            // terminate dim paths at random, weighting the survivors
+            if bounce >= MIN_BOUNCES {
+                let survival = throughput.luminance().clamp(0.05, 1.0);
+                if rng.gen::<Float>() >= survival {
+                    break
+                }
+                throughput /= survival;
+            }
+            dir = frame.to_world(sample.wi);
+            origin = Self::offset(p, frame.n, dir);
+        }
+        validate_radiance(radiance, aovs.shape_index)?;
+        if aovs.shape_index.is_some() {
+            aovs.indirect = radiance - aovs.direct;
+        }
+        Ok((radiance, aovs))
+    }
+
+}
@@ -0,0 +1,105 @@
+use crate::render::AmbientOcclusion;
+use crate::render::AmbientOcclusionRenderer;
+use crate::render::BoxedRenderer;
+use crate::render::DebugRenderer;
+use crate::render::DebugView;
+use crate::render::NaiveRenderer;
+use crate::render::PathRenderer;
+use crate::render::Renderer;
+use crate::scene::Scene;
+
+/// A function which creates a renderer referencing a scene.
+pub type RendererFactory = dyn for<'scene, 'shape> Fn(
+    &'scene Scene<'shape>
+) -> BoxedRenderer<'scene> + Send + Sync;
+
+/// A list of renderers by name, from which a renderer for a scene can be
+/// chosen at runtime, such as by a command-line flag.
+pub struct RendererRegistry {
+
+    /// The names of the renderers and the functions which create them, in
+    /// the order they were registered.
+    entries: Vec<(String, Box<RendererFactory>)>
+
+}
+
+impl RendererRegistry {
+
+    /// Creates a registry without any renderers.
+    pub fn new() -> Self {
+        RendererRegistry { entries: Vec::new() }
+    }
+
+    /// Registers the renderer created by `factory` under `name`, replacing
+    /// the renderer already registered under it, if any, in its place.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+        where F: for<'scene, 'shape> Fn(
+                     &'scene Scene<'shape>
+                 ) -> BoxedRenderer<'scene> + Send + Sync + 'static
+    {
+        let factory: Box<RendererFactory> = Box::new(factory);
+        match self.entries.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = factory,
+            None => self.entries.push((name.to_owned(), factory))
+        }
+    }
+
+    /// Returns the names of the renderers registered, in the order they were
+    /// registered.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the number of renderers registered.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether no renderers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Creates the renderer registered under `name` referencing `scene`, or
+    /// returns `None` if there is no such renderer.
+    pub fn create<'scene>(
+        &self,
+        name: &str,
+        scene: &'scene Scene<'_>
+    ) -> Option<BoxedRenderer<'scene>> {
+        self.entries.iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, factory)| factory(scene))
+    }
+
+}
+
+impl Default for RendererRegistry {
+
+    /// Returns a registry of every renderer of this crate, with typical
+    /// settings: "naive", "path", "ao", and the debug renderers "normal",
+    /// "depth", "uv", "shape" and "heatmap".
+    fn default() -> Self {
+        let mut registry = RendererRegistry::new();
+        registry.register("naive", |scene| NaiveRenderer::new(scene).boxed());
+        registry.register("path", |scene| PathRenderer::new(scene, 8).boxed());
+        registry.register("ao", |scene| {
+            let occlusion = AmbientOcclusion { samples: 4, max_distance: 1.0 };
+            AmbientOcclusionRenderer::new(scene, occlusion).boxed()
+        });
+        let views = [
+            ("normal", DebugView::Normal),
+            ("depth", DebugView::Depth { max_depth: 20.0 }),
+            ("uv", DebugView::Uv),
+            ("shape", DebugView::ShapeIndex),
+            ("heatmap", DebugView::Heatmap { max_tests: 32 })
+        ];
+        for (name, view) in views {
+            registry.register(name, move |scene| {
+                DebugRenderer::new(scene, view).boxed()
+            });
+        }
+        registry
+    }
+
+}
@@ -1,16 +1,173 @@
+use ::nalgebra::Vector3;
 use ::std::error::Error;
+use ::std::fmt;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
 use crate::Color;
+use crate::Float;
+use crate::render::Aovs;
+use crate::scene::Camera;
+use crate::scene::Ray;
 
 /// Implemented by algorithms which can render a `Scene`.
-pub trait Renderer: Sized {
+pub trait Renderer {
 
+    /// The error returned when casting a ray fails, which is `Infallible` for
+    /// renderers which cannot fail.
     type CastError: Error + Send;
 
-    /// Returns the color visible in the direction given by `theta` and `phi`.
-    fn cast_ray(
+    /// Returns the camera through which the scene is rendered, which generates
+    /// the rays cast by rasterizers.
+    fn camera(&self) -> &Camera;
+
+    /// Returns the color of the light arriving along `ray`.
+    fn cast_ray(&self, ray: &Ray) -> Result<Color, Self::CastError>;
+
+    /// Returns the color of the light arriving along `ray`, along with the
+    /// arbitrary output variables of the ray.
+    ///
+    /// By default, the output variables are those of a ray which intersects
+    /// nothing and sees the color returned by `cast_ray`.
+    fn cast_ray_with_aovs(
+        &self,
+        ray: &Ray
+    ) -> Result<(Color, Aovs), Self::CastError> {
+        let color = self.cast_ray(ray)?;
+        Ok((color, Aovs::miss(color)))
+    }
+
+    /// Returns the implementing renderer boxed behind a trait object whose
+    /// errors are `RenderError`s, so that renderers of different types can
+    /// be chosen between at runtime.
+    fn boxed<'a>(self) -> BoxedRenderer<'a>
+        where Self: Sized + Send + Sync + 'a,
+              Self::CastError: Sync + 'static
+    {
+        Box::new(Erased(self))
+    }
+
+}
+
+impl<R> Renderer for Box<R>
+    where R: Renderer + ?Sized
+{
+
+    type CastError = R::CastError;
+
+    /// Returns the camera of the boxed renderer.
+    fn camera(&self) -> &Camera {
+        (**self).camera()
+    }
+
+    /// Returns the color which the boxed renderer sees along `ray`.
+    fn cast_ray(&self, ray: &Ray) -> Result<Color, Self::CastError> {
+        (**self).cast_ray(ray)
+    }
+
+    /// Returns the color which the boxed renderer sees along `ray`, along with
+    /// the arbitrary output variables of the ray.
+    fn cast_ray_with_aovs(
+        &self,
+        ray: &Ray
+    ) -> Result<(Color, Aovs), Self::CastError> {
+        (**self).cast_ray_with_aovs(ray)
+    }
+
+}
+
+/// A renderer of any type, boxed behind a trait object whose errors are
+/// `RenderError`s, as returned by `Renderer::boxed`.
+pub type BoxedRenderer<'a> =
+    Box<dyn Renderer<CastError = RenderError> + Send + Sync + 'a>;
+
+/// An error arising from casting a ray with a `BoxedRenderer`, which wraps the
+/// error of the renderer of whatever type was boxed.
+#[derive(Debug)]
+pub struct RenderError(Box<dyn Error + Send + Sync>);
+
+impl RenderError {
+
+    /// Creates an error wrapping `error`.
+    pub fn new<E>(error: E) -> Self
+        where E: Error + Send + Sync + 'static
+    {
+        RenderError(Box::new(error))
+    }
+
+}
+
+impl Display for RenderError {
+
+    /// Formats the error wrapped by this error.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+
+}
+
+impl Error for RenderError {
+
+    /// Returns the source of the error wrapped by this error, if any.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+
+}
+
+/// Returns an error if `n`, the normal of the shape at `index` in a scene at
+/// `point`, is zero or not finite, which leaves its shading undefined.
+pub(crate) fn validate_normal(
+    n: Vector3<Float>,
+    index: usize,
+    point: Vector3<Float>
+) -> Result<(), crate::Error> {
+    if n == Vector3::zeros() || !n.iter().all(|x| x.is_finite()) {
+        Err(crate::Error::InvalidNormal { index, point })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns an error if `color`, the light arriving along a ray which first
+/// intersects the shape at `index` in a scene, if any, is not finite.
+pub(crate) fn validate_radiance(
+    color: Color,
+    index: Option<usize>
+) -> Result<(), crate::Error> {
+    if color.is_finite() {
+        Ok(())
+    } else {
+        Err(crate::Error::NonFiniteRadiance { color, index })
+    }
+}
+
+/// A renderer whose errors are converted to `RenderError`s.
+struct Erased<R>(R);
+
+impl<R> Renderer for Erased<R>
+    where R: Renderer,
+          R::CastError: Sync + 'static
+{
+
+    type CastError = RenderError;
+
+    /// Returns the camera of the wrapped renderer.
+    fn camera(&self) -> &Camera {
+        self.0.camera()
+    }
+
+    /// Returns the color which the wrapped renderer sees along `ray`.
+    fn cast_ray(&self, ray: &Ray) -> Result<Color, Self::CastError> {
+        self.0.cast_ray(ray).map_err(RenderError::new)
+    }
+
+    /// Returns the color which the wrapped renderer sees along `ray`, along
+    /// with the arbitrary output variables of the ray.
+    fn cast_ray_with_aovs(
         &self,
-        theta: f64,
-        phi: f64
-    ) -> Result<Color, Self::CastError>;
+        ray: &Ray
+    ) -> Result<(Color, Aovs), Self::CastError> {
+        self.0.cast_ray_with_aovs(ray).map_err(RenderError::new)
+    }
 
 }
@@ -0,0 +1,184 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::scene::Shutter;
+use crate::shape::Aabb;
+use crate::shape::Posed;
+use crate::shape::Shape;
+
+/// The greatest number of shapes in a leaf of a `Bvh`.
+const LEAF_SIZE: usize = 4;
+
+/// A node of a `Bvh`.
+enum Node {
+
+    /// A node containing the shapes whose indices are in the range
+    /// `start..end` of the hierarchy's list of indices.
+    Leaf {
+        bounds: Aabb,
+        start: usize,
+        end: usize
+    },
+
+    /// A node containing the nodes at `left` and `right`.
+    Branch {
+        bounds: Aabb,
+        left: usize,
+        right: usize
+    }
+
+}
+
+impl Node {
+
+    /// Returns the box bounding every shape in this node.
+    fn bounds(&self) -> &Aabb {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Branch { bounds, .. } => bounds
+        }
+    }
+
+}
+
+/// A bounding volume hierarchy over a list of shapes, which speeds up finding
+/// the first shape a ray intersects by skipping groups of shapes whose bounds
+/// it misses.
+///
+/// The bounds of moving shapes cover their motion over a shutter interval, so
+/// that a single hierarchy serves rays at any time in that interval.
+pub struct Bvh {
+
+    /// The nodes of this hierarchy, the first of which is its root.
+    nodes: Vec<Node>,
+
+    /// The indices of the bounded shapes, grouped by leaf.
+    indices: Vec<usize>,
+
+    /// The indices of the unbounded shapes, which every ray is tested against.
+    unbounded: Vec<usize>
+
+}
+
+impl Bvh {
+
+    /// Creates a hierarchy over `shapes` for rays at times during `shutter`.
+    pub fn new(shapes: &[Box<dyn Shape + '_>], shutter: &Shutter) -> Self {
+        let mut bounded = Vec::new();
+        let mut unbounded = Vec::new();
+        for (i, s) in shapes.iter().enumerate() {
+            match s.bounds_over(shutter.open, shutter.close) {
+                Some(bounds) => bounded.push((i, bounds)),
+                None => unbounded.push(i)
+            }
+        }
+        let mut bvh = Bvh { nodes: Vec::new(), indices: Vec::new(), unbounded };
+        if !bounded.is_empty() {
+            bvh.build(&mut bounded);
+        }
+        bvh
+    }
+
+    /// Adds a node containing the shapes `items`, given by their indices and
+    /// bounds, as well as its descendants, and returns the index of the node.
+    fn build(&mut self, items: &mut [(usize, Aabb)]) -> usize {
+        let bounds = items.iter()
+            .map(|(_, b)| *b)
+            .reduce(|a, b| a.union(&b))
+            .unwrap();
+        let index = self.nodes.len();
+        if items.len() <= LEAF_SIZE {
+            let start = self.indices.len();
+            self.indices.extend(items.iter().map(|(i, _)| *i));
+            let end = self.indices.len();
+            self.nodes.push(Node::Leaf { bounds, start, end });
+            return index
+        }
+        // split the shapes in half along the axis their centers spread most
+        let centers = Aabb::from_points(items.iter().map(|(_, b)| b.center()))
+            .unwrap();
+        let axis = (centers.max - centers.min).imax();
+        items.sort_unstable_by(|(_, a), (_, b)| {
+            a.center()[axis].total_cmp(&b.center()[axis])
+        });
+        let (left, right) = items.split_at_mut(items.len() / 2);
+        // reserve the place of this node before those of its descendants
+        self.nodes.push(Node::Leaf { bounds, start: 0, end: 0 });
+        let left = self.build(left);
+        let right = self.build(right);
+        self.nodes[index] = Node::Branch { bounds, left, right };
+        index
+    }
+
+    /// Returns the index in `shapes` of the first shape intersected at `time`
+    /// by a ray originating from `l0` in the direction of `l`, as well as the
+    /// point at which the intersection occurs.
+    ///
+    /// `shapes` must be the shapes over which this hierarchy was created, and
+    /// `time` must lie within the shutter interval it was created for. The
+    /// norm of `l` must be strictly positive (i.e., nonzero).
+    pub fn intersect_ray(
+        &self,
+        shapes: &[Box<dyn Shape + '_>],
+        l0: Vector3<Float>,
+        l: Vector3<Float>,
+        time: Float
+    ) -> Option<(usize, Vector3<Float>)> {
+        self.intersect_ray_counted(shapes, l0, l, time).0
+    }
+
+    /// Returns the same as `intersect_ray`, along with the number of
+    /// intersection tests it performs against shapes and bounding boxes,
+    /// which measures the cost of the ray.
+    pub fn intersect_ray_counted(
+        &self,
+        shapes: &[Box<dyn Shape + '_>],
+        l0: Vector3<Float>,
+        l: Vector3<Float>,
+        time: Float
+    ) -> (Option<(usize, Vector3<Float>)>, u32) {
+        let mut tests = 0;
+        // the closest intersection so far, with its distance along the ray
+        let mut closest: Option<(usize, Vector3<Float>, Float)> = None;
+        let test = |i: usize, closest: &mut Option<_>, tests: &mut u32| {
+            *tests += 1;
+            let shape = Posed::new(shapes[i].as_ref(), time);
+            if let Some(p) = shape.intersect_ray(l0, l) {
+                let t = (p - l0).dot(&l) / l.norm_squared();
+                if closest.is_none_or(|(_, _, t2)| t < t2) {
+                    *closest = Some((i, p, t));
+                }
+            }
+        };
+        for &i in &self.unbounded {
+            test(i, &mut closest, &mut tests);
+        }
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            // skip nodes the ray misses or only reaches beyond the closest
+            let t_max = closest.map_or(Float::INFINITY, |(_, _, t)| t);
+            tests += 1;
+            let reached = node.bounds()
+                .intersect_ray(l0, l)
+                .is_some_and(|(t_min, _)| t_min <= t_max);
+            if !reached {
+                continue
+            }
+            match *node {
+                Node::Leaf { start, end, .. } => {
+                    for &i in &self.indices[start..end] {
+                        test(i, &mut closest, &mut tests);
+                    }
+                },
+                Node::Branch { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+        (closest.map(|(i, p, _)| (i, p)), tests)
+    }
+
+}
@@ -1,28 +1,349 @@
+use ::nalgebra::Matrix3;
+use ::nalgebra::Matrix4;
+use ::nalgebra::Rotation3;
+use ::nalgebra::Unit;
 use ::nalgebra::Vector3;
+use ::nalgebra::Vector4;
+use ::std::error::Error;
+use ::std::fmt;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::sync::Arc;
+use crate::Float;
+use crate::scene::Lens;
+use crate::scene::Projection;
+use crate::scene::Ray;
+use crate::scene::Shutter;
 
-/// A directional camera.
+/// The smallest sine of the angle between the directions of "forward" and
+/// "upward" given to a camera, below which they are considered parallel.
+const MIN_SIN: Float = 1e-6;
+
+/// The largest difference between an entry of a matrix given to a camera and
+/// that of the nearest rigid transformation, beyond which it is rejected.
+const MATRIX_TOLERANCE: Float = 1e-6;
+
+/// An error arising from constructing a `Camera` with a degenerate
+/// orientation, lens or shutter.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CameraError {
+
+    /// A position, direction, angle or matrix is infinite or NaN.
+    NotFinite,
+
+    /// The direction of "forward" or "upward" is zero.
+    ZeroDirection,
+
+    /// The directions of "forward" and "upward" are parallel, which leaves
+    /// the direction of "leftward" undefined.
+    Parallel,
+
+    /// A matrix is not a rigid transformation, i.e., a rotation followed by
+    /// a translation.
+    NotRigid,
+
+    /// The radius of a lens is negative, its focal distance is not strictly
+    /// positive, its aperture has fewer than three blades or any of these is
+    /// not finite.
+    InvalidLens,
+
+    /// A shutter closes before it opens, or either time is not finite.
+    InvalidShutter
+
+}
+
+impl Display for CameraError {
+
+    /// Formats a description of the problem with the camera.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CameraError::NotFinite => "camera is not finite",
+            CameraError::ZeroDirection => "camera direction is zero",
+            CameraError::Parallel => {
+                "camera directions of forward and upward are parallel"
+            },
+            CameraError::NotRigid => "camera matrix is not rigid",
+            CameraError::InvalidLens => "camera lens is invalid",
+            CameraError::InvalidShutter => {
+                "camera shutter closes before it opens or is not finite"
+            }
+        })
+    }
+
+}
+
+impl Error for CameraError {}
+
+/// A directional camera, which is either an ideal pinhole or has a thin lens.
+///
+/// The constructors of cameras ensure that their directions of "forward" and
+/// "upward" are perpendicular unit vectors, which the methods of cameras
+/// preserve.
+///
+/// Cameras are equal if their projections are the same object and their other
+/// fields are equal.
+#[derive(Clone, Debug)]
 pub struct Camera {
 
     /// The position of this camera.
-    pub pos: Vector3<f64>,
+    pub pos: Vector3<Float>,
 
     /// The direction of "forward" for this camera.
     ///
     /// Rotation around this vector causes the camera to roll.
-    pub forward: Vector3<f64>,
+    pub forward: Vector3<Float>,
 
     /// The direction of "upward" for this camera.
     ///
     /// Rotation around this vector causes the camera to yaw.
-    pub up: Vector3<f64>
+    pub up: Vector3<Float>,
+
+    /// The projection of this camera, which maps the points of its images to
+    /// the rays entering it.
+    pub projection: Arc<dyn Projection>,
+
+    /// The lens of this camera, which blurs objects out of focus, or `None`
+    /// if it is a pinhole camera, for which everything is in focus.
+    pub lens: Option<Lens>,
+
+    /// The interval of time over which the shutter of this camera is open.
+    pub shutter: Shutter
 
 }
 
 impl Camera {
 
+    /// Creates a pinhole camera at `pos` looking in the direction of
+    /// `forward`, with `projection`, the shutter of which is open only at time
+    /// 0.0.
+    ///
+    /// Its direction of "upward" is the unit vector perpendicular to
+    /// `forward` which is closest to `up`. Returns an error if `forward` or
+    /// `up` is zero, they are parallel or any argument is not finite.
+    pub fn new(
+        pos: Vector3<Float>,
+        forward: Vector3<Float>,
+        up: Vector3<Float>,
+        projection: Arc<dyn Projection>
+    ) -> Result<Self, CameraError> {
+        if !pos.iter().chain(&forward).chain(&up).all(|x| x.is_finite()) {
+            return Err(CameraError::NotFinite)
+        }
+        let forward = forward.try_normalize(0.0)
+            .ok_or(CameraError::ZeroDirection)?;
+        let up = up.try_normalize(0.0)
+            .ok_or(CameraError::ZeroDirection)?;
+        // remove the component of "upward" along "forward"
+        let up = (up - forward * up.dot(&forward))
+            .try_normalize(MIN_SIN)
+            .ok_or(CameraError::Parallel)?;
+        Ok(Camera {
+            pos,
+            forward,
+            up,
+            projection,
+            lens: None,
+            shutter: Shutter::instant(0.0)
+        })
+    }
+
+    /// Creates a pinhole camera at `eye` looking at `target`, with
+    /// `projection`, the direction of "upward" of which is as close to
+    /// `world_up` as possible.
+    ///
+    /// Returns an error if `eye` and `target` coincide, the direction from one
+    /// to the other is parallel to `world_up` or any argument is not finite.
+    pub fn look_at(
+        eye: Vector3<Float>,
+        target: Vector3<Float>,
+        world_up: Vector3<Float>,
+        projection: Arc<dyn Projection>
+    ) -> Result<Self, CameraError> {
+        Camera::new(eye, target - eye, world_up, projection)
+    }
+
+    /// Creates a pinhole camera at `pos` with `projection` which, from
+    /// looking along the x-axis with the y-axis upward, is turned by `yaw`
+    /// rightwards, then by `pitch` upwards, then rolled by `roll`
+    /// anticlockwise, in radians.
+    ///
+    /// Returns an error if any argument is not finite.
+    pub fn from_yaw_pitch_roll(
+        pos: Vector3<Float>,
+        yaw: Float,
+        pitch: Float,
+        roll: Float,
+        projection: Arc<dyn Projection>
+    ) -> Result<Self, CameraError> {
+        if ![yaw, pitch, roll].iter().all(|x| x.is_finite()) {
+            return Err(CameraError::NotFinite)
+        }
+        let rotation = Rotation3::from_axis_angle(&Vector3::y_axis(), yaw)
+            * Rotation3::from_axis_angle(&Vector3::z_axis(), pitch)
+            * Rotation3::from_axis_angle(&Vector3::x_axis(), roll);
+        Camera::new(
+            pos,
+            rotation * Vector3::x(),
+            rotation * Vector3::y(),
+            projection
+        )
+    }
+
+    /// Creates a pinhole camera with `projection` from the rigid
+    /// transformation `matrix`, which maps the directions of "forward",
+    /// "upward" and "leftward" of the camera from the x-, y- and z-axes
+    /// respectively, and its position from the origin.
+    ///
+    /// Returns an error if `matrix` is not rigid, e.g., if it scales, shears
+    /// or reflects, or if it is not finite.
+    pub fn from_matrix(
+        matrix: &Matrix4<Float>,
+        projection: Arc<dyn Projection>
+    ) -> Result<Self, CameraError> {
+        if !matrix.iter().all(|x| x.is_finite()) {
+            return Err(CameraError::NotFinite)
+        }
+        let rotation: Matrix3<Float> = matrix.fixed_slice::<3, 3>(0, 0)
+            .into_owned();
+        let orthonormal = (rotation.transpose() * rotation)
+            .relative_eq(&Matrix3::identity(), 0.0, MATRIX_TOLERANCE);
+        let affine = matrix.row(3)
+            .transpose()
+            .relative_eq(&Vector4::w(), 0.0, MATRIX_TOLERANCE);
+        if !orthonormal || !affine || rotation.determinant() < 0.0 {
+            return Err(CameraError::NotRigid)
+        }
+        Camera::new(
+            matrix.column(3).xyz(),
+            rotation.column(0).into_owned(),
+            rotation.column(1).into_owned(),
+            projection
+        )
+    }
+
+    /// Returns an error if the position or directions of this camera are not
+    /// finite, its directions of "forward" and "upward" are zero or parallel,
+    /// which its constructors rule out but its fields allow, or its lens or
+    /// shutter is invalid.
+    pub fn validate(&self) -> Result<(), CameraError> {
+        let projection = self.projection.clone();
+        Camera::new(self.pos, self.forward, self.up, projection)?;
+        if let Some(lens) = &self.lens {
+            lens.validate()?;
+        }
+        self.shutter.validate()
+    }
+
+    /// Returns the rigid transformation which maps the directions of
+    /// "forward", "upward" and "leftward" of this camera from the x-, y- and
+    /// z-axes respectively, and its position from the origin.
+    pub fn matrix(&self) -> Matrix4<Float> {
+        let (left, up, forward) = self.frame();
+        Matrix4::from_columns(&[
+            forward.push(0.0),
+            up.push(0.0),
+            left.push(0.0),
+            self.pos.push(1.0)
+        ])
+    }
+
     /// Returns the direction of "leftward" for this camera.
-    pub fn left(&self) -> Vector3<f64> {
+    pub fn left(&self) -> Vector3<Float> {
         self.forward.cross(&self.up)
     }
 
+    /// Returns the direction which is rotated from the direction of "forward"
+    /// for this camera by `theta` leftwards, then by `phi` upwards.
+    pub fn direction(&self, theta: Float, phi: Float) -> Vector3<Float> {
+        let unit_up = Unit::new_normalize(self.up);
+        let unit_left = Unit::new_normalize(self.left());
+        Rotation3::from_axis_angle(&unit_up, theta)
+            * Rotation3::from_axis_angle(&unit_left, phi)
+            * self.forward
+    }
+
+    /// Returns the unit directions of "leftward", "upward" and "forward" for
+    /// this camera, adjusting "upward" to be perpendicular to the others.
+    pub fn frame(&self) -> (Vector3<Float>, Vector3<Float>, Vector3<Float>) {
+        let forward = self.forward.normalize();
+        let left = self.left().normalize();
+        (left, left.cross(&forward), forward)
+    }
+
+    /// Moves this camera by `distance` in its direction of "forward".
+    pub fn dolly(&mut self, distance: Float) {
+        self.pos += self.forward.normalize() * distance;
+    }
+
+    /// Moves this camera by `left` in its direction of "leftward" and by `up`
+    /// in its direction of "upward", without turning it.
+    pub fn pan(&mut self, left: Float, up: Float) {
+        let (unit_left, unit_up, _) = self.frame();
+        self.pos += unit_left * left + unit_up * up;
+    }
+
+    /// Turns this camera in place by `rotation`.
+    pub fn rotate(&mut self, rotation: &Rotation3<Float>) {
+        self.forward = rotation * self.forward;
+        self.up = rotation * self.up;
+    }
+
+    /// Moves this camera around `target` by `rotation`, turning it along with
+    /// its position so that its view of `target` is unchanged.
+    pub fn orbit(
+        &mut self,
+        target: Vector3<Float>,
+        rotation: &Rotation3<Float>
+    ) {
+        self.pos = target + rotation * (self.pos - target);
+        self.rotate(rotation);
+    }
+
+    /// Returns a ray entering this camera through the point `(x, y)` of an
+    /// image whose width is `aspect` times its height, as mapped by its
+    /// projection, or `None` if no ray enters through it.
+    ///
+    /// `x` and `y` run from 0.0 to 1.0 rightwards and downwards across the
+    /// image respectively. The ray passes through the point on the lens chosen
+    /// by `u.xy()`, at the time during the shutter interval chosen by `u.z`,
+    /// each component of which must lie between 0.0 and 1.0. Averaging the
+    /// light arriving along rays for many random values of `u` blurs objects
+    /// out of focus and in motion, while `u.xy()` of zero chooses the center of
+    /// the lens.
+    pub fn ray(
+        &self,
+        x: Float,
+        y: Float,
+        aspect: Float,
+        u: Vector3<Float>
+    ) -> Option<Ray> {
+        let (pinhole, dir) = self.projection.ray(self, x, y, aspect)?;
+        let time = self.shutter.sample(u.z);
+        let (left, up, forward) = self.frame();
+        let cos = dir.dot(&forward) / dir.norm();
+        let Some(lens) = self.lens.filter(|_| cos > 0.0) else {
+            return Some(Ray { origin: pinhole, dir, time })
+        };
+        // the point on the plane in focus which the ray passes through
+        let focus = pinhole + dir.normalize() * (lens.focal_distance / cos);
+        let a = lens.sample(u.xy());
+        let origin = pinhole + left * a.x + up * a.y;
+        Some(Ray { origin, dir: (focus - origin).normalize(), time })
+    }
+
+}
+
+impl PartialEq for Camera {
+
+    /// Returns whether this camera and `other` have the same projection and
+    /// equal positions, directions, lenses and shutters.
+    fn eq(&self, other: &Camera) -> bool {
+        self.pos == other.pos
+            && self.forward == other.forward
+            && self.up == other.up
+            && Arc::ptr_eq(&self.projection, &other.projection)
+            && self.lens == other.lens
+            && self.shutter == other.shutter
+    }
+
 }
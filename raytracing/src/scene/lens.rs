@@ -0,0 +1,164 @@
+use ::nalgebra::Vector2;
+use crate::Float;
+use crate::consts::PI;
+use crate::scene::CameraError;
+
+/// The shape of the aperture of a `Lens`, which is the shape of out-of-focus
+/// highlights (bokeh).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Aperture {
+
+    /// A circular aperture.
+    Circle,
+
+    /// A regular polygonal aperture, as formed by the blades of an iris
+    /// diaphragm.
+    Polygon {
+
+        /// The number of blades, which is the number of sides of the polygon.
+        /// There must be at least three.
+        blades: u32,
+
+        /// The angle of the first vertex of the polygon, from leftwards
+        /// towards upwards.
+        rotation: Float
+
+    }
+
+}
+
+/// A thin lens, which brings light from a single distance into focus.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Lens {
+
+    /// The radius of the aperture of this lens, which is the radius of the
+    /// circle circumscribing it if it is polygonal.
+    ///
+    /// Larger apertures blur out-of-focus objects more.
+    pub radius: Float,
+
+    /// The distance along the camera's forward direction to the plane which
+    /// is in focus.
+    pub focal_distance: Float,
+
+    /// The shape of the aperture of this lens.
+    pub aperture: Aperture
+
+}
+
+impl Lens {
+
+    /// Creates a lens of focal length `focal_length` stopped down to the
+    /// f-number `f_number`, focused at the distance `focal_distance`.
+    ///
+    /// The focal length and focal distance are in the units of the scene.
+    /// Returns an error if the focal length or f-number is not strictly
+    /// positive, or the lens would be invalid (see `validate`).
+    pub fn from_f_number(
+        focal_length: Float,
+        f_number: Float,
+        focal_distance: Float,
+        aperture: Aperture
+    ) -> Result<Self, CameraError> {
+        if !(focal_length > 0.0 && f_number > 0.0) {
+            return Err(CameraError::InvalidLens)
+        }
+        let lens = Lens {
+            radius: focal_length / (2.0 * f_number),
+            focal_distance,
+            aperture
+        };
+        lens.validate()?;
+        Ok(lens)
+    }
+
+    /// Returns an error if the radius of this lens is negative, its focal
+    /// distance is not strictly positive, its aperture has fewer than three
+    /// blades or any of these is not finite.
+    pub fn validate(&self) -> Result<(), CameraError> {
+        let aperture = match self.aperture {
+            Aperture::Circle => true,
+            Aperture::Polygon { blades, rotation } => {
+                blades >= 3 && rotation.is_finite()
+            }
+        };
+        let valid = self.radius.is_finite()
+            && self.focal_distance.is_finite()
+            && self.radius >= 0.0
+            && self.focal_distance > 0.0;
+        if valid && aperture {
+            Ok(())
+        } else {
+            Err(CameraError::InvalidLens)
+        }
+    }
+
+    /// Returns a point on the aperture of this lens, leftwards and upwards
+    /// from its center, chosen uniformly over its area.
+    ///
+    /// Each component of `u` must be a random number uniformly distributed
+    /// between 0.0 and 1.0.
+    pub fn sample(&self, u: Vector2<Float>) -> Vector2<Float> {
+        let point = match self.aperture {
+            Aperture::Circle => {
+                let r = u.x.sqrt();
+                let phi = 2.0 * PI * u.y;
+                Vector2::new(r * phi.cos(), r * phi.sin())
+            },
+            Aperture::Polygon { blades, rotation } => {
+                let blades = blades.max(3) as Float;
+                // choose one of the triangles fanning out from the center
+                let k = (u.x * blades).floor().min(blades - 1.0);
+                let v = |k: Float| {
+                    let phi = rotation + 2.0 * PI * k / blades;
+                    Vector2::new(phi.cos(), phi.sin())
+                };
+                // reuse u.x to choose a point uniformly within the triangle
+                let s = (u.x * blades - k).sqrt();
+                (v(k) * (1.0 - u.y) + v(k + 1.0) * u.y) * s
+            }
+        };
+        point * self.radius
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::Float;
+    use crate::scene::CameraError;
+    use super::Aperture;
+    use super::Lens;
+
+    #[test]
+    fn from_f_number() {
+        let lens = Lens::from_f_number(0.05, 2.0, 3.0, Aperture::Circle);
+        assert_eq!(lens.map(|lens| lens.radius), Ok(0.0125));
+    }
+
+    #[test]
+    fn invalid_f_number() {
+        let polygon = Aperture::Polygon { blades: 2, rotation: 0.0 };
+        for (focal_length, f_number, focal_distance, aperture) in [
+            (0.05, 0.0, 3.0, Aperture::Circle),
+            (0.05, -2.0, 3.0, Aperture::Circle),
+            (0.0, 2.0, 3.0, Aperture::Circle),
+            (0.05, Float::NAN, 3.0, Aperture::Circle),
+            (Float::INFINITY, 2.0, 3.0, Aperture::Circle),
+            (0.05, 2.0, 0.0, Aperture::Circle),
+            (0.05, 2.0, 3.0, polygon)
+        ] {
+            assert_eq!(
+                Lens::from_f_number(
+                    focal_length,
+                    f_number,
+                    focal_distance,
+                    aperture
+                ),
+                Err(CameraError::InvalidLens)
+            );
+        }
+    }
+
+}
@@ -1,11 +1,13 @@
 use ::nalgebra::Vector3;
 use crate::Color;
+use crate::Error;
+use crate::Float;
 
 /// A uniform, chromatic point light source.
 pub struct Light {
 
     /// The position of this light source.
-    pub pos: Vector3<f64>,
+    pub pos: Vector3<Float>,
 
     /// The color of light shed by this light source which contributes to
     /// diffuse reflection.
@@ -16,3 +18,31 @@ pub struct Light {
     pub specular_color: Color
 
 }
+
+impl Light {
+
+    /// Creates a light source at `pos` which sheds the colors `diffuse_color`
+    /// and `specular_color`.
+    ///
+    /// Returns an error if a color is negative or any argument is not finite.
+    pub fn new(
+        pos: Vector3<Float>,
+        diffuse_color: Color,
+        specular_color: Color
+    ) -> Result<Self, Error> {
+        let light = Light { pos, diffuse_color, specular_color };
+        light.validate()?;
+        Ok(light)
+    }
+
+    /// Returns an error if one of the colors of this light source is negative
+    /// or any of its quantities is not finite.
+    pub fn validate(&self) -> Result<(), Error> {
+        if !self.pos.iter().all(|x| x.is_finite()) {
+            return Err(Error::NotFinite("light position"))
+        }
+        self.diffuse_color.validate("light diffuse color")?;
+        self.specular_color.validate("light specular color")
+    }
+
+}
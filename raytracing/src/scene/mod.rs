@@ -1,10 +1,21 @@
 /// Contains `Scene`, the primary object which is rendered, as well as some of
-/// its components, `Camera` and `Light` (but not `Shape`).
+/// its components, `Camera`, `Lens` and `Light` (but not `Shape`), and the
+/// projections and rays of cameras.
 
+mod bvh;
 mod camera;
+mod lens;
 mod light;
+mod projection;
+mod ray;
 mod scene;
+mod shutter;
 
+pub use bvh::*;
 pub use camera::*;
+pub use lens::*;
 pub use light::*;
+pub use projection::*;
+pub use ray::*;
 pub use scene::*;
+pub use shutter::*;
@@ -0,0 +1,192 @@
+use ::nalgebra::Vector3;
+use ::std::fmt::Debug;
+use crate::Float;
+use crate::consts::PI;
+use crate::scene::Camera;
+
+/// Implemented by projections, which map the points of an image taken by a
+/// `Camera` to the rays entering it.
+pub trait Projection: Debug + Send + Sync {
+
+    /// Returns the origin and direction of the ray entering `camera` through
+    /// the point `(x, y)` of an image whose width is `aspect` times its
+    /// height, or `None` if no ray enters through it.
+    ///
+    /// `x` and `y` run from 0.0 to 1.0 rightwards and downwards across the
+    /// image respectively.
+    fn ray(
+        &self,
+        camera: &Camera,
+        x: Float,
+        y: Float,
+        aspect: Float
+    ) -> Option<(Vector3<Float>, Vector3<Float>)>;
+
+}
+
+/// A perspective projection, in which the angles of rays from the forward
+/// direction are proportional to the distances of their points from the
+/// center of the image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Perspective {
+
+    /// The horizontal field of view, in radians.
+    pub fov_horiz: Float,
+
+    /// The vertical field of view, in radians.
+    pub fov_vert: Float
+
+}
+
+impl Projection for Perspective {
+
+    /// Returns the ray from the position of `camera` rotated from its forward
+    /// direction in proportion to the offset of `(x, y)` from the center of
+    /// the image.
+    fn ray(
+        &self,
+        camera: &Camera,
+        x: Float,
+        y: Float,
+        _aspect: Float
+    ) -> Option<(Vector3<Float>, Vector3<Float>)> {
+        let theta = (x - 0.5) * self.fov_horiz;
+        let phi = (0.5 - y) * self.fov_vert;
+        Some((camera.pos, camera.direction(theta, phi)))
+    }
+
+}
+
+/// An orthographic projection, in which every ray is parallel to the forward
+/// direction, so that distant objects do not appear smaller.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Orthographic {
+
+    /// The height of the area seen, in the units of the scene.
+    pub height: Float
+
+}
+
+impl Projection for Orthographic {
+
+    /// Returns the ray in the forward direction of `camera` from the point
+    /// `(x, y)` of a rectangle centered on its position.
+    fn ray(
+        &self,
+        camera: &Camera,
+        x: Float,
+        y: Float,
+        aspect: Float
+    ) -> Option<(Vector3<Float>, Vector3<Float>)> {
+        let (left, up, forward) = camera.frame();
+        let offset = left * (0.5 - x) * self.height * aspect
+            + up * (0.5 - y) * self.height;
+        Some((camera.pos + offset, forward))
+    }
+
+}
+
+/// The mapping of a `Fisheye` projection from angles from the forward
+/// direction to distances from the center of the image.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FisheyeMapping {
+
+    /// The distance is proportional to the angle.
+    Equidistant,
+
+    /// The distance is proportional to the sine of half of the angle, which
+    /// preserves areas.
+    Equisolid
+
+}
+
+/// A fisheye projection, which maps a wide field of view to a circle
+/// inscribed in the image, such as a hemisphere for dome projection.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fisheye {
+
+    /// The field of view across the diameter of the circle, in radians, which
+    /// may be up to 2π.
+    pub fov: Float,
+
+    /// The mapping from angles to distances from the center of the image.
+    pub mapping: FisheyeMapping
+
+}
+
+impl Projection for Fisheye {
+
+    /// Returns the ray from the position of `camera` in the direction mapped
+    /// to `(x, y)`, or `None` if it lies outside the circle.
+    fn ray(
+        &self,
+        camera: &Camera,
+        x: Float,
+        y: Float,
+        aspect: Float
+    ) -> Option<(Vector3<Float>, Vector3<Float>)> {
+        // the offset from the center, in diameters of the circle
+        let scale = aspect.min(1.0);
+        let (sx, sy) = ((x - 0.5) * aspect / scale, (0.5 - y) / scale);
+        let r = 2.0 * sx.hypot(sy);
+        if r > 1.0 {
+            return None
+        }
+        let max_angle = self.fov / 2.0;
+        let angle = match self.mapping {
+            FisheyeMapping::Equidistant => r * max_angle,
+            FisheyeMapping::Equisolid => {
+                2.0 * (r * (max_angle / 2.0).sin()).asin()
+            }
+        };
+        let (left, up, forward) = camera.frame();
+        let radial = (up * sy - left * sx).try_normalize(0.0)
+            .unwrap_or(up);
+        let dir = forward * angle.cos() + radial * angle.sin();
+        Some((camera.pos, dir))
+    }
+
+}
+
+/// An equirectangular projection, which maps longitude and latitude around
+/// the camera linearly to the width and height of the image respectively,
+/// as used by 360° panoramas.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Equirectangular {
+
+    /// The distance between the eyes of an omni-directional stereo (ODS)
+    /// panorama, the top half of which is seen by the left eye and the bottom
+    /// half by the right, or `None` for a monoscopic panorama.
+    pub ipd: Option<Float>
+
+}
+
+impl Projection for Equirectangular {
+
+    /// Returns the ray in the direction at the longitude and latitude mapped
+    /// to `(x, y)`, from the position of `camera` or, in stereo, from the eye
+    /// on a circle around it looking in that direction.
+    fn ray(
+        &self,
+        camera: &Camera,
+        x: Float,
+        y: Float,
+        _aspect: Float
+    ) -> Option<(Vector3<Float>, Vector3<Float>)> {
+        // in stereo, the left eye is offset leftwards and the right rightwards
+        let (y, eye) = match self.ipd {
+            Some(ipd) if y < 0.5 => (2.0 * y, ipd / 2.0),
+            Some(ipd) => (2.0 * y - 1.0, -ipd / 2.0),
+            None => (y, 0.0)
+        };
+        let longitude = (x - 0.5) * 2.0 * PI;
+        let latitude = (0.5 - y) * PI;
+        let (left, up, forward) = camera.frame();
+        // the horizontal component of the direction
+        let horizontal = forward * longitude.cos() - left * longitude.sin();
+        let dir = horizontal * latitude.cos() + up * latitude.sin();
+        let origin = camera.pos + horizontal.cross(&up) * eye;
+        Some((origin, dir))
+    }
+
+}
@@ -0,0 +1,20 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+
+/// A ray entering a camera, along which light is traced backwards into the
+/// scene.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+
+    /// The point from which this ray originates.
+    pub origin: Vector3<Float>,
+
+    /// The direction of this ray, the norm of which is strictly positive
+    /// (i.e., nonzero).
+    pub dir: Vector3<Float>,
+
+    /// The time at which this ray enters the camera, at which moving shapes
+    /// are intersected.
+    pub time: Float
+
+}
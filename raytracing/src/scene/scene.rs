@@ -1,6 +1,13 @@
+use ::nalgebra::Vector3;
+use ::rayon::iter::IndexedParallelIterator;
+use ::rayon::iter::IntoParallelRefIterator;
+use ::rayon::iter::ParallelIterator;
 use crate::Color;
+use crate::Error;
+use crate::Float;
 use crate::scene::Camera;
 use crate::scene::Light;
+use crate::shape::Posed;
 use crate::shape::Shape;
 
 /// The primary container object which is rendered.
@@ -22,3 +29,222 @@ pub struct Scene<'shape> {
     pub shapes: Vec<Box<dyn Shape + 'shape>>,
 
 }
+
+impl<'shape> Scene<'shape> {
+
+    /// Returns the first shape intersected by ray originating from `l0` in the
+    /// direction of `l`, as well as the point at which the intersection occurs.
+    ///
+    /// The norm of `l` must be strictly positive (i.e., nonzero).
+    pub fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<(&(dyn Shape + 'shape), Vector3<Float>)> {
+        self.intersect_ray_index(l0, l)
+            .map(|(i, p)| (self.shapes[i].as_ref(), p))
+    }
+
+    /// Returns the index in `shapes` of the first shape intersected by ray
+    /// originating from `l0` in the direction of `l`, as well as the point at
+    /// which the intersection occurs.
+    ///
+    /// The norm of `l` must be strictly positive (i.e., nonzero).
+    pub fn intersect_ray_index(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<(usize, Vector3<Float>)> {
+        self.shapes.par_iter()
+            .enumerate()
+            /* zip shapes with their intersection points and filter out the
+             * ones with no such point */
+            .filter_map(|(i, s)| s.intersect_ray(l0, l).map(|p| (i, p)))
+            // select the shape closest to the origin of the ray
+            .min_by(|(_, p1), (_, p2)| {
+                let d1 = (p1 - l0).norm();
+                let d2 = (p2 - l0).norm();
+                d1.total_cmp(&d2)
+            })
+    }
+
+    /// Returns the index in `shapes` of the first shape intersected at `time`
+    /// by a ray originating from `l0` in the direction of `l`, as well as the
+    /// point at which the intersection occurs.
+    ///
+    /// The norm of `l` must be strictly positive (i.e., nonzero).
+    pub fn intersect_ray_index_at(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>,
+        time: Float
+    ) -> Option<(usize, Vector3<Float>)> {
+        self.shapes.par_iter()
+            .enumerate()
+            .filter_map(|(i, s)| {
+                Posed::new(s.as_ref(), time)
+                    .intersect_ray(l0, l)
+                    .map(|p| (i, p))
+            })
+            // select the shape closest to the origin of the ray
+            .min_by(|(_, p1), (_, p2)| {
+                let d1 = (p1 - l0).norm();
+                let d2 = (p2 - l0).norm();
+                d1.total_cmp(&d2)
+            })
+    }
+
+    /// Returns the shape at index `i` in `shapes` in the pose it has at
+    /// `time`.
+    ///
+    /// Panics if there is no such shape.
+    pub fn shape_at(&self, i: usize, time: Float) -> Posed<'_> {
+        Posed::new(self.shapes[i].as_ref(), time)
+    }
+
+    /// Returns an error if the camera, a light source or a shape of this scene
+    /// is invalid, or any of its colors is negative or not finite.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.background_color.validate("background color")?;
+        self.ambient_color.validate("ambient color")?;
+        self.camera.validate()?;
+        for (index, light) in self.lights.iter().enumerate() {
+            light.validate().map_err(|cause| {
+                Error::InvalidLight { index, cause: Box::new(cause) }
+            })?;
+        }
+        for (index, shape) in self.shapes.iter().enumerate() {
+            shape.validate().map_err(|cause| {
+                Error::InvalidShape { index, cause: Box::new(cause) }
+            })?;
+        }
+        Ok(())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ::nalgebra::Vector3;
+    use ::std::sync::Arc;
+    use crate::Color;
+    use crate::Error;
+    use crate::Float;
+    use crate::scene::Aperture;
+    use crate::scene::Camera;
+    use crate::scene::CameraError;
+    use crate::scene::Lens;
+    use crate::scene::Light;
+    use crate::scene::Orthographic;
+    use crate::scene::Shutter;
+    use crate::shape::Motion;
+    use crate::shape::Moving;
+    use crate::shape::Sphere;
+    use super::Scene;
+
+    /// Returns a white sphere of radius 0.5 at `x` along the x-axis.
+    fn sphere(x: Float) -> Sphere {
+        Sphere::new(
+            Vector3::new(x, 0.0, 0.0),
+            0.5,
+            Color::WHITE,
+            Color::WHITE,
+            Color::WHITE,
+            2.0
+        ).unwrap()
+    }
+
+    /// Returns a valid scene of two spheres lit by one light source.
+    fn scene() -> Scene<'static> {
+        let projection = Arc::new(Orthographic { height: 2.0 });
+        let camera = Camera::new(
+            Vector3::new(-5.0, 0.0, 0.0),
+            Vector3::x(),
+            Vector3::y(),
+            projection
+        ).unwrap();
+        Scene {
+            background_color: Color::BLACK,
+            ambient_color: Color::WHITE * 0.1,
+            camera,
+            lights: vec![
+                Light::new(Vector3::y(), Color::WHITE, Color::WHITE).unwrap()
+            ],
+            shapes: vec![Box::new(sphere(0.0)), Box::new(sphere(2.0))]
+        }
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(scene().validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_colors() {
+        let mut scene = scene();
+        scene.ambient_color = Color { r: 0.0, g: Float::NAN, b: 0.0 };
+        let error = Error::NotFinite("ambient color");
+        assert_eq!(scene.validate(), Err(error));
+    }
+
+    #[test]
+    fn invalid_light() {
+        let mut scene = scene();
+        scene.lights.push(Light {
+            pos: Vector3::zeros(),
+            diffuse_color: Color::WHITE * -1.0,
+            specular_color: Color::WHITE
+        });
+        let error = scene.validate().unwrap_err();
+        assert!(matches!(error, Error::InvalidLight { index: 1, .. }));
+        assert!(error.to_string().starts_with("invalid light at index 1: "));
+    }
+
+    #[test]
+    fn invalid_shape() {
+        let mut scene = scene();
+        let mut sphere = sphere(4.0);
+        sphere.r = -1.0;
+        scene.shapes.push(Box::new(sphere));
+        assert_eq!(scene.validate(), Err(Error::InvalidShape {
+            index: 2,
+            cause: Box::new(Error::NonPositiveRadius(-1.0))
+        }));
+    }
+
+    #[test]
+    fn invalid_motion() {
+        let mut scene = scene();
+        let velocity = Vector3::repeat(Float::INFINITY);
+        scene.shapes.push(Box::new(Moving {
+            shape: sphere(4.0),
+            motion: Motion::Linear { velocity }
+        }));
+        assert_eq!(scene.validate(), Err(Error::InvalidShape {
+            index: 2,
+            cause: Box::new(Error::NotFinite("motion velocity"))
+        }));
+    }
+
+    #[test]
+    fn invalid_camera() {
+        let mut scene = scene();
+        scene.camera.up = scene.camera.forward;
+        let error = Error::Camera(CameraError::Parallel);
+        assert_eq!(scene.validate(), Err(error));
+        let mut scene = self::scene();
+        scene.camera.lens = Some(Lens {
+            radius: -0.1,
+            focal_distance: 1.0,
+            aperture: Aperture::Circle
+        });
+        let error = Error::Camera(CameraError::InvalidLens);
+        assert_eq!(scene.validate(), Err(error));
+        let mut scene = self::scene();
+        scene.camera.shutter = Shutter { open: 1.0, close: 0.0 };
+        let error = Error::Camera(CameraError::InvalidShutter);
+        assert_eq!(scene.validate(), Err(error));
+    }
+
+}
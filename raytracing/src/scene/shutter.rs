@@ -0,0 +1,44 @@
+use crate::Float;
+use crate::scene::CameraError;
+
+/// The interval of time over which the shutter of a camera is open, over
+/// which moving shapes are blurred.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Shutter {
+
+    /// The time at which this shutter opens.
+    pub open: Float,
+
+    /// The time at which this shutter closes, which is no earlier than the
+    /// time at which it opens.
+    pub close: Float
+
+}
+
+impl Shutter {
+
+    /// Creates a shutter which is open only at `time`, so that nothing is
+    /// blurred.
+    pub fn instant(time: Float) -> Self {
+        Shutter { open: time, close: time }
+    }
+
+    /// Returns a time at which this shutter is open, chosen uniformly.
+    ///
+    /// `u` must be a random number uniformly distributed between 0.0 and 1.0.
+    pub fn sample(&self, u: Float) -> Float {
+        self.open + (self.close - self.open) * u
+    }
+
+    /// Returns an error if this shutter closes before it opens, or either time
+    /// is not finite.
+    pub fn validate(&self) -> Result<(), CameraError> {
+        let finite = self.open.is_finite() && self.close.is_finite();
+        if finite && self.open <= self.close {
+            Ok(())
+        } else {
+            Err(CameraError::InvalidShutter)
+        }
+    }
+
+}
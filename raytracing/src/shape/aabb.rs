@@ -0,0 +1,99 @@
+use ::nalgebra::Isometry3;
+use ::nalgebra::Point3;
+use ::nalgebra::Vector3;
+use crate::Float;
+
+/// An axis-aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+
+    /// The corner of this box with the least coordinates.
+    pub min: Vector3<Float>,
+
+    /// The corner of this box with the greatest coordinates.
+    pub max: Vector3<Float>
+
+}
+
+impl Aabb {
+
+    /// Returns the smallest box containing every one of `points`, or `None`
+    /// if there are none.
+    pub fn from_points<I>(points: I) -> Option<Self>
+        where I: IntoIterator<Item = Vector3<Float>>
+    {
+        points.into_iter()
+            .map(|p| Aabb { min: p, max: p })
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Returns the smallest box containing both this box and `other`.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max)
+        }
+    }
+
+    /// Returns the center of this box.
+    pub fn center(&self) -> Vector3<Float> {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Returns the corners of this box.
+    pub fn corners(&self) -> [Vector3<Float>; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vector3::new(a.x, a.y, a.z),
+            Vector3::new(b.x, a.y, a.z),
+            Vector3::new(a.x, b.y, a.z),
+            Vector3::new(b.x, b.y, a.z),
+            Vector3::new(a.x, a.y, b.z),
+            Vector3::new(b.x, a.y, b.z),
+            Vector3::new(a.x, b.y, b.z),
+            Vector3::new(b.x, b.y, b.z)
+        ]
+    }
+
+    /// Returns this box grown by `margin` in every direction.
+    pub fn expanded(&self, margin: Float) -> Aabb {
+        let margin = Vector3::repeat(margin);
+        Aabb { min: self.min - margin, max: self.max + margin }
+    }
+
+    /// Returns this box moved by `offset`.
+    pub fn translated(&self, offset: Vector3<Float>) -> Aabb {
+        Aabb { min: self.min + offset, max: self.max + offset }
+    }
+
+    /// Returns the smallest box containing this box transformed by
+    /// `transform`.
+    pub fn transformed(&self, transform: &Isometry3<Float>) -> Aabb {
+        let corners = self.corners()
+            .map(|c| transform.transform_point(&Point3::from(c)).coords);
+        Aabb::from_points(corners).unwrap()
+    }
+
+    /// Returns the values of `t` between which the ray `l0 + t * l`, for
+    /// nonnegative `t`, lies within this box, or `None` if it misses it.
+    pub fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<(Float, Float)> {
+        let (mut t_min, mut t_max) = (0.0, Float::INFINITY);
+        for i in 0..3 {
+            // the values of t at which the ray crosses the two slabs
+            let t1 = (self.min[i] - l0[i]) / l[i];
+            let t2 = (self.max[i] - l0[i]) / l[i];
+            // NaN arises from rays which lie in the plane of a slab
+            if t1.is_nan() || t2.is_nan() {
+                continue
+            }
+            t_min = t1.min(t2).max(t_min);
+            t_max = t1.max(t2).min(t_max);
+        }
+        if t_min <= t_max { Some((t_min, t_max)) } else { None }
+    }
+
+}
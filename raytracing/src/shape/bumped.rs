@@ -0,0 +1,188 @@
+use ::nalgebra::Isometry3;
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::material::Material;
+use crate::shape::Aabb;
+use crate::shape::Shape;
+use crate::texture::Texture;
+use crate::texture::UvTexture;
+
+/// The step used to estimate the derivatives of height textures by finite
+/// differences.
+const EPSILON: Float = 1.0e-4;
+
+/// A map which perturbs the normals of a shape.
+pub enum BumpMap<'tex> {
+
+    /// A bump map given by a solid texture of heights above the surface.
+    ///
+    /// The normal is tilted away from the gradient of the heights, scaled by
+    /// `scale`.
+    Height {
+        texture: Box<dyn Texture<Float> + 'tex>,
+        scale: Float
+    },
+
+    /// A bump map given by a texture of heights above the surface over its
+    /// texture coordinates, such as a grayscale image.
+    ///
+    /// The normal is tilted away from the derivatives of the heights along
+    /// the tangent and bitangent, scaled by `scale`.
+    UvHeight {
+        texture: Box<dyn UvTexture<Float> + 'tex>,
+        scale: Float
+    },
+
+    /// A normal map in tangent space, such as those baked by modeling tools.
+    ///
+    /// The red, green and blue components of each color, mapped from `[0, 1]`
+    /// to `[-1, 1]`, are the components of the normal along the tangent, the
+    /// bitangent and the unperturbed normal respectively.
+    Normal(Box<dyn UvTexture<Color> + 'tex>)
+
+}
+
+/// A shape whose normals are perturbed by a bump map or a normal map, giving
+/// the impression of surface detail without altering its geometry.
+pub struct Bumped<'tex, S> {
+
+    /// The underlying shape, which determines the geometry of this shape.
+    pub shape: S,
+
+    /// The map which perturbs the normals of the underlying shape.
+    pub map: BumpMap<'tex>
+
+}
+
+impl<S> Bumped<'_, S> where S: Shape {
+
+    /// Returns the unit normal, tangent and bitangent of the underlying shape
+    /// at `p`, which form a right-handed frame.
+    fn frame_at(
+        &self,
+        p: Vector3<Float>
+    ) -> (Vector3<Float>, Vector3<Float>, Vector3<Float>) {
+        let n = self.shape.normal_at(p).normalize();
+        let t = self.shape.tangent_at(p);
+        // make the tangent exactly perpendicular to the normal
+        let t = (t - t.dot(&n) * n).normalize();
+        (n, t, n.cross(&t))
+    }
+
+}
+
+impl<S> Shape for Bumped<'_, S> where S: Shape {
+
+    /// Returns the color of the underlying shape at `p` in ambient white
+    /// light.
+    fn ambient_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.ambient_color_at(p)
+    }
+
+    /// Returns the color of the diffuse reflection of white light off of the
+    /// underlying shape at `p`.
+    fn diffuse_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.diffuse_color_at(p)
+    }
+
+    /// Returns the color of the specular reflection of white light off of the
+    /// underlying shape at `p`.
+    fn specular_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.specular_color_at(p)
+    }
+
+    /// Returns the shininess of the underlying shape at `p`.
+    fn shininess_at(&self, p: Vector3<Float>) -> Float {
+        self.shape.shininess_at(p)
+    }
+
+    /// Returns the point at which a ray originating from `l0` in the direction
+    /// of `l` intersects the underlying shape.
+    fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<Vector3<Float>> {
+        self.shape.intersect_ray(l0, l)
+    }
+
+    /// Returns a unit vector normal to the underlying shape at `p`, perturbed
+    /// by the map of this shape.
+    ///
+    /// `p` must be a point on the underlying shape.
+    fn normal_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        let (n, t, b) = self.frame_at(p);
+        let perturbed = match &self.map {
+            BumpMap::Height { texture, scale } => {
+                // the gradient of the height, by central differences
+                let grad = Vector3::from_fn(|i, _| {
+                    let mut e = Vector3::zeros();
+                    e[i] = EPSILON;
+                    (texture.value_at(p + e) - texture.value_at(p - e))
+                        / (2.0 * EPSILON)
+                });
+                // only the component of the gradient along the surface tilts it
+                n - *scale * (grad - grad.dot(&n) * n)
+            },
+            BumpMap::UvHeight { texture, scale } => {
+                let uv = self.shape.uv_at(p);
+                let du = Vector2::new(EPSILON, 0.0);
+                let dv = Vector2::new(0.0, EPSILON);
+                let dh_du = (texture.value_at_uv(uv + du)
+                    - texture.value_at_uv(uv - du)) / (2.0 * EPSILON);
+                let dh_dv = (texture.value_at_uv(uv + dv)
+                    - texture.value_at_uv(uv - dv)) / (2.0 * EPSILON);
+                n - *scale * (dh_du * t + dh_dv * b)
+            },
+            BumpMap::Normal(texture) => {
+                let c = texture.value_at_uv(self.shape.uv_at(p));
+                // map each component from [0, 1] to [-1, 1]
+                (2.0 * c.r - 1.0) * t + (2.0 * c.g - 1.0) * b
+                    + (2.0 * c.b - 1.0) * n
+            }
+        };
+        // fall back to the unperturbed normal if the map is degenerate here
+        perturbed.try_normalize(0.0).unwrap_or(n)
+    }
+
+    /// Returns the texture coordinates of the underlying shape at `p`.
+    fn uv_at(&self, p: Vector3<Float>) -> Vector2<Float> {
+        self.shape.uv_at(p)
+    }
+
+    /// Returns a unit vector tangent to the underlying shape at `p`.
+    fn tangent_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.shape.tangent_at(p)
+    }
+
+    /// Returns the material of the underlying shape at `p`.
+    fn material_at(&self, p: Vector3<Float>) -> Option<&dyn Material> {
+        self.shape.material_at(p)
+    }
+
+    /// Returns a box bounding the underlying shape.
+    fn bounds(&self) -> Option<Aabb> {
+        self.shape.bounds()
+    }
+
+    /// Returns a box bounding the underlying shape at every time between
+    /// `open` and `close`.
+    fn bounds_over(&self, open: Float, close: Float) -> Option<Aabb> {
+        self.shape.bounds_over(open, close)
+    }
+
+    /// Returns the transformation of the underlying shape from rest at
+    /// `time`.
+    fn motion_at(&self, time: Float) -> Option<Isometry3<Float>> {
+        self.shape.motion_at(time)
+    }
+
+    /// Returns an error if the underlying shape is invalid.
+    fn validate(&self) -> Result<(), Error> {
+        self.shape.validate()
+    }
+
+}
@@ -1,10 +1,22 @@
 /// Contains `Shape`, a trait implemented by objects which can appear in a
 /// `Scene`, as well as various types that implement `Shape`.
 
+mod aabb;
+mod bumped;
+mod motion;
+mod moving;
 mod shape;
 mod sphere;
+mod textured;
 mod triangle;
+mod with_material;
 
+pub use aabb::*;
+pub use bumped::*;
+pub use motion::*;
+pub use moving::*;
 pub use shape::*;
 pub use sphere::*;
+pub use textured::*;
 pub use triangle::*;
+pub use with_material::*;
@@ -0,0 +1,183 @@
+use ::nalgebra::Isometry3;
+use ::nalgebra::Point3;
+use ::nalgebra::Vector3;
+use crate::Error;
+use crate::Float;
+use crate::shape::Aabb;
+
+/// The motion of a shape over time, as a rigid transformation from its pose
+/// at rest.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Motion {
+
+    /// Translation at the constant velocity `velocity`, in units per unit of
+    /// time, such that the shape is at rest at time 0.0.
+    Linear {
+
+        /// The velocity of the shape.
+        velocity: Vector3<Float>
+
+    },
+
+    /// Interpolation between poses at increasing times, holding the first
+    /// and last poses before and after them respectively.
+    ///
+    /// Translations are interpolated linearly and rotations spherically.
+    Keyframes(Vec<(Float, Isometry3<Float>)>)
+
+}
+
+impl Motion {
+
+    /// Returns the transformation from rest of a shape with this motion at
+    /// `time`.
+    pub fn at(&self, time: Float) -> Isometry3<Float> {
+        match self {
+            Motion::Linear { velocity } => {
+                let v = velocity * time;
+                Isometry3::translation(v.x, v.y, v.z)
+            },
+            Motion::Keyframes(frames) => {
+                // the index of the first keyframe after time
+                let i = frames.partition_point(|&(t, _)| t <= time);
+                if frames.is_empty() {
+                    Isometry3::identity()
+                } else if i == 0 {
+                    frames[0].1
+                } else if i == frames.len() {
+                    frames[i - 1].1
+                } else {
+                    let (t0, a) = frames[i - 1];
+                    let (t1, b) = frames[i];
+                    a.lerp_slerp(&b, (time - t0) / (t1 - t0))
+                }
+            }
+        }
+    }
+
+    /// Returns an error if the velocity of this motion, or the time or pose of
+    /// any of its keyframes, is not finite, or the times of its keyframes do
+    /// not strictly increase.
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            Motion::Linear { velocity } => {
+                if !velocity.iter().all(|x| x.is_finite()) {
+                    return Err(Error::NotFinite("motion velocity"))
+                }
+            },
+            Motion::Keyframes(frames) => {
+                let finite = frames.iter().all(|(t, pose)| {
+                    t.is_finite()
+                        && pose.translation.vector.iter().all(|x| x.is_finite())
+                        && pose.rotation.coords.iter().all(|x| x.is_finite())
+                });
+                if !finite {
+                    return Err(Error::NotFinite("motion keyframe"))
+                }
+                if frames.windows(2).any(|w| w[0].0 >= w[1].0) {
+                    return Err(Error::UnorderedKeyframes)
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a box bounding a shape with this motion, bounded by `bounds` at
+    /// rest, at every time between `open` and `close`.
+    pub fn sweep(&self, bounds: &Aabb, open: Float, close: Float) -> Aabb {
+        match self {
+            Motion::Linear { velocity } => {
+                bounds.translated(velocity * open)
+                    .union(&bounds.translated(velocity * close))
+            },
+            Motion::Keyframes(frames) => {
+                // the times between which the motion is a single interpolation
+                let mut times = vec![open];
+                times.extend(frames.iter()
+                    .map(|&(t, _)| t)
+                    .filter(|&t| open < t && t < close));
+                times.push(close);
+                times.windows(2)
+                    .map(|w| self.sweep_segment(bounds, w[0], w[1]))
+                    .reduce(|a, b| a.union(&b))
+                    .unwrap()
+            }
+        }
+    }
+
+    /// Returns a box bounding a shape bounded by `bounds` at rest at every
+    /// time between `t0` and `t1`, between which its pose is interpolated
+    /// directly.
+    fn sweep_segment(&self, bounds: &Aabb, t0: Float, t1: Float) -> Aabb {
+        let (a, b) = (self.at(t0), self.at(t1));
+        // bound the shape by a sphere, the center of which follows the motion
+        let c = Point3::from(bounds.center());
+        let r = (bounds.max - bounds.min).norm() / 2.0;
+        /* the center is translated along a line and rotated along an arc, which
+         * lies within the chord between its ends widened by its sagitta */
+        let angle = (a.rotation.inverse() * b.rotation).angle();
+        let sagitta = c.coords.norm() * (1.0 - (angle / 2.0).cos());
+        let translations = Aabb::from_points([
+            a.translation.vector,
+            b.translation.vector
+        ]).unwrap();
+        let rotations = Aabb::from_points([
+            (a.rotation * c).coords,
+            (b.rotation * c).coords
+        ]).unwrap();
+        Aabb {
+            min: translations.min + rotations.min,
+            max: translations.max + rotations.max
+        }.expanded(sagitta + r)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ::nalgebra::Isometry3;
+    use ::nalgebra::Vector3;
+    use crate::Error;
+    use crate::Float;
+    use super::Motion;
+
+    /// Returns keyframes at `times` translated along the x-axis by their time.
+    fn keyframes(times: &[Float]) -> Motion {
+        Motion::Keyframes(times.iter()
+            .map(|&t| (t, Isometry3::translation(t, 0.0, 0.0)))
+            .collect())
+    }
+
+    #[test]
+    fn valid() {
+        let velocity = Vector3::x();
+        assert_eq!(Motion::Linear { velocity }.validate(), Ok(()));
+        assert_eq!(keyframes(&[]).validate(), Ok(()));
+        assert_eq!(keyframes(&[-1.0, 0.0, 2.5]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn not_finite() {
+        let velocity = Vector3::new(0.0, Float::NAN, 0.0);
+        assert_eq!(
+            Motion::Linear { velocity }.validate(),
+            Err(Error::NotFinite("motion velocity"))
+        );
+        assert_eq!(
+            keyframes(&[0.0, Float::INFINITY]).validate(),
+            Err(Error::NotFinite("motion keyframe"))
+        );
+    }
+
+    #[test]
+    fn unordered_keyframes() {
+        for times in [&[1.0, 0.0][..], &[0.0, 1.0, 1.0], &[0.0, 2.0, 1.0]] {
+            assert_eq!(
+                keyframes(times).validate(),
+                Err(Error::UnorderedKeyframes)
+            );
+        }
+    }
+
+}
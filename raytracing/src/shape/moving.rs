@@ -0,0 +1,231 @@
+use ::nalgebra::Isometry3;
+use ::nalgebra::Point3;
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::material::Material;
+use crate::shape::Aabb;
+use crate::shape::Motion;
+use crate::shape::Shape;
+
+/// A shape which moves over time, blurring it in renderers which sample the
+/// camera's shutter interval.
+///
+/// Its methods which take no time describe it at rest, where the underlying
+/// shape is.
+pub struct Moving<S> {
+
+    /// The underlying shape, which determines this shape at rest.
+    pub shape: S,
+
+    /// The motion of this shape from rest.
+    pub motion: Motion
+
+}
+
+impl<S> Shape for Moving<S> where S: Shape {
+
+    /// Returns the color of the underlying shape at `p` in ambient white
+    /// light.
+    fn ambient_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.ambient_color_at(p)
+    }
+
+    /// Returns the color of the diffuse reflection of white light off of the
+    /// underlying shape at `p`.
+    fn diffuse_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.diffuse_color_at(p)
+    }
+
+    /// Returns the color of the specular reflection of white light off of the
+    /// underlying shape at `p`.
+    fn specular_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.specular_color_at(p)
+    }
+
+    /// Returns the shininess of the underlying shape at `p`.
+    fn shininess_at(&self, p: Vector3<Float>) -> Float {
+        self.shape.shininess_at(p)
+    }
+
+    /// Returns the point at which a ray originating from `l0` in the direction
+    /// of `l` intersects the underlying shape.
+    fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<Vector3<Float>> {
+        self.shape.intersect_ray(l0, l)
+    }
+
+    /// Returns a vector normal to the underlying shape at `p`.
+    fn normal_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.shape.normal_at(p)
+    }
+
+    /// Returns the texture coordinates of the underlying shape at `p`.
+    fn uv_at(&self, p: Vector3<Float>) -> Vector2<Float> {
+        self.shape.uv_at(p)
+    }
+
+    /// Returns a unit vector tangent to the underlying shape at `p`.
+    fn tangent_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.shape.tangent_at(p)
+    }
+
+    /// Returns the material of the underlying shape at `p`.
+    fn material_at(&self, p: Vector3<Float>) -> Option<&dyn Material> {
+        self.shape.material_at(p)
+    }
+
+    /// Returns a box bounding the underlying shape.
+    fn bounds(&self) -> Option<Aabb> {
+        self.shape.bounds()
+    }
+
+    /// Returns a box bounding this shape at every time between `open` and
+    /// `close`.
+    fn bounds_over(&self, open: Float, close: Float) -> Option<Aabb> {
+        self.shape.bounds_over(open, close)
+            .map(|bounds| self.motion.sweep(&bounds, open, close))
+    }
+
+    /// Returns the transformation of this shape from rest at `time`, which is
+    /// that of the underlying shape followed by its own.
+    fn motion_at(&self, time: Float) -> Option<Isometry3<Float>> {
+        let inner = self.shape.motion_at(time)
+            .unwrap_or_else(Isometry3::identity);
+        Some(self.motion.at(time) * inner)
+    }
+
+    /// Returns an error if the underlying shape or the motion of this shape is
+    /// invalid.
+    fn validate(&self) -> Result<(), Error> {
+        self.shape.validate()?;
+        self.motion.validate()
+    }
+
+}
+
+/// A shape frozen in the pose it has at a particular time, which is how
+/// renderers intersect and shade shapes at the time of a ray.
+pub struct Posed<'a> {
+
+    /// The underlying shape, which determines this shape at rest.
+    shape: &'a dyn Shape,
+
+    /// The transformation of the underlying shape from rest, or `None` if it
+    /// does not move.
+    pose: Option<Isometry3<Float>>
+
+}
+
+impl<'a> Posed<'a> {
+
+    /// Creates the shape `shape` in the pose it has at `time`.
+    pub fn new(shape: &'a dyn Shape, time: Float) -> Self {
+        Posed { shape, pose: shape.motion_at(time) }
+    }
+
+    /// Returns the point `p` transformed from this pose back to rest.
+    fn point_at_rest(&self, p: Vector3<Float>) -> Vector3<Float> {
+        match &self.pose {
+            Some(pose) => pose.inverse_transform_point(&Point3::from(p)).coords,
+            None => p
+        }
+    }
+
+    /// Returns the vector `v` at rest rotated into this pose.
+    fn vector_in_pose(&self, v: Vector3<Float>) -> Vector3<Float> {
+        match &self.pose {
+            Some(pose) => pose.rotation * v,
+            None => v
+        }
+    }
+
+}
+
+impl Shape for Posed<'_> {
+
+    /// Returns the color of the underlying shape at `p` in ambient white
+    /// light.
+    fn ambient_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.ambient_color_at(self.point_at_rest(p))
+    }
+
+    /// Returns the color of the diffuse reflection of white light off of the
+    /// underlying shape at `p`.
+    fn diffuse_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.diffuse_color_at(self.point_at_rest(p))
+    }
+
+    /// Returns the color of the specular reflection of white light off of the
+    /// underlying shape at `p`.
+    fn specular_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.specular_color_at(self.point_at_rest(p))
+    }
+
+    /// Returns the shininess of the underlying shape at `p`.
+    fn shininess_at(&self, p: Vector3<Float>) -> Float {
+        self.shape.shininess_at(self.point_at_rest(p))
+    }
+
+    /// Returns the point at which a ray originating from `l0` in the direction
+    /// of `l` intersects this shape, by intersecting the ray transformed back
+    /// to rest with the underlying shape.
+    fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<Vector3<Float>> {
+        let Some(pose) = &self.pose else {
+            return self.shape.intersect_ray(l0, l)
+        };
+        let l0 = pose.inverse_transform_point(&Point3::from(l0)).coords;
+        let l = pose.inverse_transform_vector(&l);
+        self.shape.intersect_ray(l0, l)
+            .map(|p| pose.transform_point(&Point3::from(p)).coords)
+    }
+
+    /// Returns a vector normal to this shape at `p`.
+    fn normal_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.vector_in_pose(self.shape.normal_at(self.point_at_rest(p)))
+    }
+
+    /// Returns the texture coordinates of the underlying shape at `p`.
+    fn uv_at(&self, p: Vector3<Float>) -> Vector2<Float> {
+        self.shape.uv_at(self.point_at_rest(p))
+    }
+
+    /// Returns a unit vector tangent to this shape at `p`.
+    fn tangent_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.vector_in_pose(self.shape.tangent_at(self.point_at_rest(p)))
+    }
+
+    /// Returns the material of the underlying shape at `p`.
+    fn material_at(&self, p: Vector3<Float>) -> Option<&dyn Material> {
+        self.shape.material_at(self.point_at_rest(p))
+    }
+
+    /// Returns a box bounding this shape.
+    fn bounds(&self) -> Option<Aabb> {
+        let bounds = self.shape.bounds()?;
+        Some(match &self.pose {
+            Some(pose) => bounds.transformed(pose),
+            None => bounds
+        })
+    }
+
+    /// Returns `None`, since this shape is frozen in its pose.
+    fn motion_at(&self, _time: Float) -> Option<Isometry3<Float>> {
+        None
+    }
+
+    /// Returns an error if the underlying shape is invalid.
+    fn validate(&self) -> Result<(), Error> {
+        self.shape.validate()
+    }
+
+}
@@ -1,5 +1,11 @@
+use ::nalgebra::Isometry3;
+use ::nalgebra::Vector2;
 use ::nalgebra::Vector3;
 use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::material::Material;
+use crate::shape::Aabb;
 
 /// Implemented by objects which can appear in a `Scene`.
 pub trait Shape: Send + Sync {
@@ -9,27 +15,27 @@ pub trait Shape: Send + Sync {
     ///
     /// Its implementations may require `p` to be a point on the implementing
     /// shape.
-    fn ambient_color_at(&self, p: Vector3<f64>) -> Color;
+    fn ambient_color_at(&self, p: Vector3<Float>) -> Color;
 
     /// Returns the color of the diffuse reflection of white light off of the
     /// implementing shape at `p`.
     ///
     /// Its implementations may require `p` to be a point on the implementing
     /// shape.
-    fn diffuse_color_at(&self, p: Vector3<f64>) -> Color;
+    fn diffuse_color_at(&self, p: Vector3<Float>) -> Color;
 
     /// Returns the color of the specular reflection of white light off of the
     /// implementing shape at `p`.
     ///
     /// Its implementations may require `p` to be a point on the implementing
     /// shape.
-    fn specular_color_at(&self, p: Vector3<f64>) -> Color;
+    fn specular_color_at(&self, p: Vector3<Float>) -> Color;
 
     /// Returns the shininess of the implementing shape at `p`.
     ///
     /// Its implementations may require `p` to be a point on the implementing
     /// shape.
-    fn shininess_at(&self, p: Vector3<f64>) -> f64;
+    fn shininess_at(&self, p: Vector3<Float>) -> Float;
 
     /// Returns the point at which a ray originating from `l0` in the direction
     /// of `l` intersects the implementing shape.
@@ -38,14 +44,92 @@ pub trait Shape: Send + Sync {
     /// (i.e., nonzero).
     fn intersect_ray(
         &self,
-        l0: Vector3<f64>,
-        l: Vector3<f64>
-    ) -> Option<Vector3<f64>>;
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<Vector3<Float>>;
 
     /// Returns a vector normal to the implementing shape at `p`.
     ///
     /// Its implementations may require `p` to be a point on the implementing
     /// shape.
-    fn normal_at(&self, p: Vector3<f64>) -> Vector3<f64>;
+    fn normal_at(&self, p: Vector3<Float>) -> Vector3<Float>;
 
+    /// Returns the texture coordinates of the implementing shape at `p`.
+    ///
+    /// Its implementations may require `p` to be a point on the implementing
+    /// shape. By default, every point has the texture coordinates `(0, 0)`.
+    fn uv_at(&self, _p: Vector3<Float>) -> Vector2<Float> {
+        Vector2::zeros()
+    }
+
+    /// Returns a unit vector tangent to the implementing shape at `p`, in the
+    /// direction in which its first texture coordinate increases.
+    ///
+    /// Together with the normal, it determines the frame in which normal maps
+    /// are interpreted. Its implementations may require `p` to be a point on
+    /// the implementing shape. By default, it returns an arbitrary unit vector
+    /// perpendicular to the normal at `p`.
+    fn tangent_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        perpendicular_to(self.normal_at(p))
+    }
+
+    /// Returns the material of the implementing shape at `p`, which describes
+    /// how it scatters light in physically based renderers.
+    ///
+    /// Its implementations may require `p` to be a point on the implementing
+    /// shape. By default, a shape has no material, and renderers fall back to
+    /// its colors and shininess.
+    fn material_at(&self, _p: Vector3<Float>) -> Option<&dyn Material> {
+        None
+    }
+
+    /// Returns a box bounding the implementing shape at rest, or `None` if it
+    /// is unbounded.
+    ///
+    /// By default, a shape is unbounded, so it is tested against every ray.
+    fn bounds(&self) -> Option<Aabb> {
+        None
+    }
+
+    /// Returns a box bounding the implementing shape at every time between
+    /// `open` and `close`, or `None` if it is unbounded.
+    ///
+    /// By default, a shape does not move, so this is its bounds at rest.
+    fn bounds_over(&self, _open: Float, _close: Float) -> Option<Aabb> {
+        self.bounds()
+    }
+
+    /// Returns the rigid transformation of the implementing shape from rest at
+    /// `time`, or `None` if it does not move.
+    ///
+    /// The other methods of a shape describe it at rest, and renderers which
+    /// sample time transform rays to and from rest to intersect and shade it.
+    /// By default, a shape does not move.
+    fn motion_at(&self, _time: Float) -> Option<Isometry3<Float>> {
+        None
+    }
+
+    /// Returns an error if the implementing shape is degenerate or any of its
+    /// quantities is not finite, which renderers cannot handle.
+    ///
+    /// By default, a shape is valid.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+}
+
+/// Returns an arbitrary unit vector perpendicular to `n`.
+///
+/// The norm of `n` must be strictly positive (i.e., nonzero).
+pub fn perpendicular_to(n: Vector3<Float>) -> Vector3<Float> {
+    // cross n with whichever axis is least parallel to it
+    let axis = if n.x.abs() < n.y.abs() && n.x.abs() < n.z.abs() {
+        Vector3::x()
+    } else if n.y.abs() < n.z.abs() {
+        Vector3::y()
+    } else {
+        Vector3::z()
+    };
+    n.cross(&axis).normalize()
 }
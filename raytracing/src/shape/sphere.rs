@@ -1,16 +1,22 @@
+use ::nalgebra::Vector2;
 use ::nalgebra::Vector3;
 use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::consts::PI;
+use crate::shape::Aabb;
 use crate::shape::Shape;
+use crate::shape::perpendicular_to;
 
 /// A sphere.
 #[derive(PartialEq)]
 pub struct Sphere {
 
     /// The origin of this sphere.
-    pub o: Vector3<f64>,
+    pub o: Vector3<Float>,
 
     /// The radius of this sphere.
-    pub r: f64,
+    pub r: Float,
 
     /// The color of this sphere in ambient white light.
     pub ambient_color: Color,
@@ -22,31 +28,61 @@ pub struct Sphere {
     pub specular_color: Color,
 
     /// The shininess of this sphere.
-    pub shininess: f64
+    pub shininess: Float
+
+}
+
+impl Sphere {
+
+    /// Creates a sphere with origin `o`, radius `r`, the colors
+    /// `ambient_color`, `diffuse_color` and `specular_color` in white light and
+    /// shininess `shininess`.
+    ///
+    /// Returns an error if the radius is not positive, a color is negative or
+    /// any argument is not finite.
+    pub fn new(
+        o: Vector3<Float>,
+        r: Float,
+        ambient_color: Color,
+        diffuse_color: Color,
+        specular_color: Color,
+        shininess: Float
+    ) -> Result<Self, Error> {
+        let sphere = Sphere {
+            o,
+            r,
+            ambient_color,
+            diffuse_color,
+            specular_color,
+            shininess
+        };
+        sphere.validate()?;
+        Ok(sphere)
+    }
 
 }
 
 impl Shape for Sphere {
 
     /// Returns the color of this sphere at `p` in ambient white light.
-    fn ambient_color_at(&self, _p: Vector3<f64>) -> Color {
+    fn ambient_color_at(&self, _p: Vector3<Float>) -> Color {
         self.ambient_color
     }
 
     /// Returns the color of the diffuse reflection of white light off of this
     /// sphere at `p`.
-    fn diffuse_color_at(&self, _p: Vector3<f64>) -> Color {
+    fn diffuse_color_at(&self, _p: Vector3<Float>) -> Color {
         self.diffuse_color
     }
 
     /// Returns the color of the specular reflection of white light off of this
     /// sphere at `p`.
-    fn specular_color_at(&self, _p: Vector3<f64>) -> Color {
+    fn specular_color_at(&self, _p: Vector3<Float>) -> Color {
         self.specular_color
     }
 
     /// Returns the shininess of this sphere at `p`.
-    fn shininess_at(&self, _p: Vector3<f64>) -> f64 {
+    fn shininess_at(&self, _p: Vector3<Float>) -> Float {
         self.shininess
     }
 
@@ -56,9 +92,9 @@ impl Shape for Sphere {
     /// The norm of `l` must be strictly positive (i.e., nonzero).
     fn intersect_ray(
         &self,
-        l0: Vector3<f64>,
-        l: Vector3<f64>,
-    ) -> Option<Vector3<f64>>
+        l0: Vector3<Float>,
+        l: Vector3<Float>,
+    ) -> Option<Vector3<Float>>
     {
         // the displacement from the ray's origin to the sphere's center
         let d = self.o - l0;
@@ -67,20 +103,125 @@ impl Shape for Sphere {
         if d.dot(&l) <= 0.0 && d.norm() >= self.r {
             return None
         }
-        // the projection of the sphere's center onto the ray
-        let p = l0 + d.dot(&l) / l.norm_squared() * l;
+        // the coefficient of l in the projection of the center onto the ray
+        let t = d.dot(&l) / l.norm_squared();
+        let p = l0 + t * l;
         // the square of the distance from the projection to the intersection
         let k2 = self.r * self.r - (self.o - p).norm_squared();
         if k2 >= 0.0 {
-            Some(p - k2.sqrt() * l)
+            // the coefficient of l in the displacement to the intersection
+            let k = (k2 / l.norm_squared()).sqrt();
+            /* take the nearer intersection, unless the ray originates inside
+             * the sphere, in which case it lies behind the ray's origin */
+            if t - k >= 0.0 {
+                Some(p - k * l)
+            } else {
+                Some(p + k * l)
+            }
         } else {
             None
         }
     }
 
     /// Returns a vector normal to this sphere at `p`.
-    fn normal_at(&self, p: Vector3<f64>) -> Vector3<f64> {
+    fn normal_at(&self, p: Vector3<Float>) -> Vector3<Float> {
         p - self.o
     }
 
+    /// Returns the texture coordinates of this sphere at `p`.
+    ///
+    /// The first coordinate is the longitude of `p` around the y-axis and the
+    /// second is its latitude, both scaled to lie between 0.0 and 1.0.
+    fn uv_at(&self, p: Vector3<Float>) -> Vector2<Float> {
+        let d = (p - self.o) / self.r;
+        Vector2::new(
+            0.5 + (-d.z).atan2(d.x) / (2.0 * PI),
+            0.5 + d.y.clamp(-1.0, 1.0).asin() / PI
+        )
+    }
+
+    /// Returns a unit vector tangent to this sphere at `p`, in the direction in
+    /// which its longitude increases.
+    ///
+    /// At the poles, where the longitude is undefined, it returns an arbitrary
+    /// unit vector tangent to this sphere.
+    fn tangent_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        let d = p - self.o;
+        let t = Vector3::new(d.z, 0.0, -d.x);
+        if t.norm_squared() > 1.0e-12 * self.r * self.r {
+            t.normalize()
+        } else {
+            perpendicular_to(d)
+        }
+    }
+
+    /// Returns the box bounding this sphere.
+    fn bounds(&self) -> Option<Aabb> {
+        let r = Vector3::repeat(self.r);
+        Some(Aabb { min: self.o - r, max: self.o + r })
+    }
+
+    /// Returns an error if the radius of this sphere is not positive, one of
+    /// its colors is negative or any of its quantities is not finite.
+    fn validate(&self) -> Result<(), Error> {
+        if !self.o.iter().all(|x| x.is_finite()) {
+            return Err(Error::NotFinite("sphere origin"))
+        }
+        if !self.r.is_finite() {
+            return Err(Error::NotFinite("sphere radius"))
+        }
+        if self.r <= 0.0 {
+            return Err(Error::NonPositiveRadius(self.r))
+        }
+        self.ambient_color.validate("sphere ambient color")?;
+        self.diffuse_color.validate("sphere diffuse color")?;
+        self.specular_color.validate("sphere specular color")?;
+        if !self.shininess.is_finite() {
+            return Err(Error::NotFinite("sphere shininess"))
+        }
+        Ok(())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ::nalgebra::Vector3;
+    use crate::Color;
+    use crate::Error;
+    use crate::Float;
+    use super::Sphere;
+
+    /// Returns the result of creating a white sphere at `o` of radius `r`.
+    fn sphere(o: Vector3<Float>, r: Float) -> Result<Sphere, Error> {
+        Sphere::new(o, r, Color::WHITE, Color::WHITE, Color::WHITE, 2.0)
+    }
+
+    #[test]
+    fn valid() {
+        assert!(sphere(Vector3::new(1.0, 2.0, 3.0), 0.5).is_ok());
+    }
+
+    #[test]
+    fn invalid_radius() {
+        let o = Vector3::zeros();
+        assert_eq!(sphere(o, 0.0).err(), Some(Error::NonPositiveRadius(0.0)));
+        assert_eq!(sphere(o, -1.0).err(), Some(Error::NonPositiveRadius(-1.0)));
+        assert_eq!(
+            sphere(o, Float::NAN).err(),
+            Some(Error::NotFinite("sphere radius"))
+        );
+    }
+
+    #[test]
+    fn invalid_origin_or_color() {
+        let o = Vector3::new(0.0, Float::INFINITY, 0.0);
+        let error = Error::NotFinite("sphere origin");
+        assert_eq!(sphere(o, 1.0).err(), Some(error));
+        let red = Color { r: -1.0, g: 0.0, b: 0.0 };
+        let result = Sphere::new(Vector3::zeros(), 1.0, red, red, red, 2.0);
+        assert_eq!(result.err(), Some(Error::Negative("sphere ambient color")));
+    }
+
 }
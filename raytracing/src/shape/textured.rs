@@ -0,0 +1,140 @@
+use ::nalgebra::Isometry3;
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::material::Material;
+use crate::shape::Aabb;
+use crate::shape::Shape;
+use crate::texture::Texture;
+
+/// A shape whose colors and shininess are given by textures, in place of
+/// those of the underlying shape.
+pub struct Textured<'tex, S> {
+
+    /// The underlying shape, which determines the geometry of this shape.
+    pub shape: S,
+
+    /// The texture giving the color of this shape in ambient white light, or
+    /// `None` to use that of the underlying shape.
+    pub ambient_color: Option<Box<dyn Texture<Color> + 'tex>>,
+
+    /// The texture giving the color of the diffuse reflection of white light
+    /// off of this shape, or `None` to use that of the underlying shape.
+    pub diffuse_color: Option<Box<dyn Texture<Color> + 'tex>>,
+
+    /// The texture giving the color of the specular reflection of white light
+    /// off of this shape, or `None` to use that of the underlying shape.
+    pub specular_color: Option<Box<dyn Texture<Color> + 'tex>>,
+
+    /// The texture giving the shininess of this shape, or `None` to use that
+    /// of the underlying shape.
+    pub shininess: Option<Box<dyn Texture<Float> + 'tex>>
+
+}
+
+impl<S> Textured<'_, S> {
+
+    /// Creates a shape which is `shape` with none of its properties replaced.
+    pub fn new(shape: S) -> Self {
+        Textured {
+            shape,
+            ambient_color: None,
+            diffuse_color: None,
+            specular_color: None,
+            shininess: None
+        }
+    }
+
+}
+
+impl<S> Shape for Textured<'_, S> where S: Shape {
+
+    /// Returns the color of this shape at `p` in ambient white light.
+    fn ambient_color_at(&self, p: Vector3<Float>) -> Color {
+        match &self.ambient_color {
+            Some(texture) => texture.value_at(p),
+            None => self.shape.ambient_color_at(p)
+        }
+    }
+
+    /// Returns the color of the diffuse reflection of white light off of this
+    /// shape at `p`.
+    fn diffuse_color_at(&self, p: Vector3<Float>) -> Color {
+        match &self.diffuse_color {
+            Some(texture) => texture.value_at(p),
+            None => self.shape.diffuse_color_at(p)
+        }
+    }
+
+    /// Returns the color of the specular reflection of white light off of this
+    /// shape at `p`.
+    fn specular_color_at(&self, p: Vector3<Float>) -> Color {
+        match &self.specular_color {
+            Some(texture) => texture.value_at(p),
+            None => self.shape.specular_color_at(p)
+        }
+    }
+
+    /// Returns the shininess of this shape at `p`.
+    fn shininess_at(&self, p: Vector3<Float>) -> Float {
+        match &self.shininess {
+            Some(texture) => texture.value_at(p),
+            None => self.shape.shininess_at(p)
+        }
+    }
+
+    /// Returns the point at which a ray originating from `l0` in the direction
+    /// of `l` intersects the underlying shape.
+    fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<Vector3<Float>> {
+        self.shape.intersect_ray(l0, l)
+    }
+
+    /// Returns a vector normal to the underlying shape at `p`.
+    fn normal_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.shape.normal_at(p)
+    }
+
+    /// Returns the texture coordinates of the underlying shape at `p`.
+    fn uv_at(&self, p: Vector3<Float>) -> Vector2<Float> {
+        self.shape.uv_at(p)
+    }
+
+    /// Returns a unit vector tangent to the underlying shape at `p`.
+    fn tangent_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.shape.tangent_at(p)
+    }
+
+    /// Returns the material of the underlying shape at `p`.
+    fn material_at(&self, p: Vector3<Float>) -> Option<&dyn Material> {
+        self.shape.material_at(p)
+    }
+
+    /// Returns a box bounding the underlying shape.
+    fn bounds(&self) -> Option<Aabb> {
+        self.shape.bounds()
+    }
+
+    /// Returns a box bounding the underlying shape at every time between
+    /// `open` and `close`.
+    fn bounds_over(&self, open: Float, close: Float) -> Option<Aabb> {
+        self.shape.bounds_over(open, close)
+    }
+
+    /// Returns the transformation of the underlying shape from rest at
+    /// `time`.
+    fn motion_at(&self, time: Float) -> Option<Isometry3<Float>> {
+        self.shape.motion_at(time)
+    }
+
+    /// Returns an error if the underlying shape is invalid.
+    fn validate(&self) -> Result<(), Error> {
+        self.shape.validate()
+    }
+
+}
@@ -1,20 +1,29 @@
 use ::nalgebra::Matrix3;
+use ::nalgebra::Vector2;
 use ::nalgebra::Vector3;
 use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::shape::Aabb;
 use crate::shape::Shape;
+use crate::shape::perpendicular_to;
+
+/// The smallest sine of an angle of a triangle, below which its vertices are
+/// considered collinear, which must be coarser at lower precision.
+const MIN_SIN: Float = 1.0e4 * Float::EPSILON;
 
 /// A triangle.
 #[derive(PartialEq)]
 pub struct Triangle {
 
     /// The first vertex of this triangle.
-    pub a: Vector3<f64>,
+    pub a: Vector3<Float>,
 
     /// The second vertex of this triangle.
-    pub b: Vector3<f64>,
+    pub b: Vector3<Float>,
 
     /// The third vertex of this triangle.
-    pub c: Vector3<f64>,
+    pub c: Vector3<Float>,
 
     /// The color of this triangle in ambient white light.
     pub ambient_color: Color,
@@ -26,41 +35,107 @@ pub struct Triangle {
     /// triangle.
     pub specular_color: Color,
 
-    /// The shininess of this sphere.
-    pub shininess: f64
+    /// The shininess of this triangle.
+    pub shininess: Float,
+
+    /// The texture coordinates of the vertices `a`, `b` and `c` of this
+    /// triangle, in that order.
+    ///
+    /// If `None`, they are taken to be `(0, 0)`, `(1, 0)` and `(0, 1)`.
+    pub uvs: Option<[Vector2<Float>; 3]>
 
 }
 
 impl Triangle {
 
+    /// Creates a triangle with the vertices `vertices`, the colors
+    /// `ambient_color`, `diffuse_color` and `specular_color` in white light,
+    /// shininess `shininess` and the texture coordinates of its vertices
+    /// `uvs`, if any.
+    ///
+    /// Returns an error if the vertices are collinear, a color is negative or
+    /// any argument is not finite.
+    pub fn new(
+        vertices: [Vector3<Float>; 3],
+        ambient_color: Color,
+        diffuse_color: Color,
+        specular_color: Color,
+        shininess: Float,
+        uvs: Option<[Vector2<Float>; 3]>
+    ) -> Result<Self, Error> {
+        let [a, b, c] = vertices;
+        let triangle = Triangle {
+            a,
+            b,
+            c,
+            ambient_color,
+            diffuse_color,
+            specular_color,
+            shininess,
+            uvs
+        };
+        triangle.validate()?;
+        Ok(triangle)
+    }
+
     /// Returns a vector normal to this triangle.
-    fn normal(&self) -> Vector3<f64> {
+    fn normal(&self) -> Vector3<Float> {
         (self.b - self.a).cross(&(self.c - self.a))
     }
 
+    /// Returns the barycentric coordinates `(v, w)` of the projection of `p`
+    /// onto the plane of this triangle, such that the projection is
+    /// `a + v * (b - a) + w * (c - a)`.
+    ///
+    /// Returns `None` if this triangle is degenerate.
+    fn barycentric(&self, p: Vector3<Float>) -> Option<(Float, Float)> {
+        // a transformation matrix from barycentric to Cartesian coordinates
+        let mut m = Matrix3::from_columns(&[
+            self.b - self.a,
+            self.c - self.a,
+            self.normal()
+        ]);
+        // invert it to obtain a transformation from Cartesian to barycentric
+        if m.try_inverse_mut() {
+            let q = m * (p - self.a);
+            Some((q[0], q[1]))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the texture coordinates of the vertices of this triangle.
+    fn vertex_uvs(&self) -> [Vector2<Float>; 3] {
+        self.uvs.unwrap_or([
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0)
+        ])
+    }
+
 }
 
 impl Shape for Triangle {
 
     /// Returns the color of this triangle at `p` in ambient white light.
-    fn ambient_color_at(&self, _p: Vector3<f64>) -> Color {
+    fn ambient_color_at(&self, _p: Vector3<Float>) -> Color {
         self.ambient_color
     }
 
     /// Returns the color of the diffuse reflection of white light off of this
     /// triangle at `p`.
-    fn diffuse_color_at(&self, _p: Vector3<f64>) -> Color {
+    fn diffuse_color_at(&self, _p: Vector3<Float>) -> Color {
         self.diffuse_color
     }
 
     /// Returns the color of the specular reflection of white light off of this
     /// triangle at `p`.
-    fn specular_color_at(&self, _p: Vector3<f64>) -> Color {
+    fn specular_color_at(&self, _p: Vector3<Float>) -> Color {
         self.specular_color
     }
 
     /// Returns the shininess of this triangle at `p`.
-    fn shininess_at(&self, _p: Vector3<f64>) -> f64 {
+    fn shininess_at(&self, _p: Vector3<Float>) -> Float {
         self.shininess
     }
 
@@ -70,9 +145,9 @@ impl Shape for Triangle {
     /// The norm of `l` must be strictly positive (i.e., nonzero).
     fn intersect_ray(
         &self,
-        l0: Vector3<f64>,
-        l: Vector3<f64>,
-    ) -> Option<Vector3<f64>>
+        l0: Vector3<Float>,
+        l: Vector3<Float>,
+    ) -> Option<Vector3<Float>>
     {
         let n = self.normal();
         // if the ray is parallel with the triangle
@@ -87,22 +162,10 @@ impl Shape for Triangle {
         }
         // the intersection between the line and the coplanar plane
         let p = l0 + k * l;
-        // a transformation matrix from barycentric to Cartesian coordinates
-        let mut m = Matrix3::from_columns(&[
-            self.b - self.a,
-            self.c - self.a,
-            n
-        ]);
-        // invert it to obtain a transformation from Cartesian to barycentric
-        if m.try_inverse_mut() {
-            let q = m * (p - self.a);
-            let (v, w) = (q[0], q[1]);
-            // if p is contained in the triangle
-            if v >= 0.0 && w >= 0.0 && v + w <= 1.0 {
-                Some(p)
-            } else {
-                None
-            }
+        let (v, w) = self.barycentric(p)?;
+        // if p is contained in the triangle
+        if v >= 0.0 && w >= 0.0 && v + w <= 1.0 {
+            Some(p)
         } else {
             None
         }
@@ -111,8 +174,125 @@ impl Shape for Triangle {
     /// Returns a vector normal to this triangle at `p`.
     ///
     /// `p` must be a point on this triangle.
-    fn normal_at(&self, _p: Vector3<f64>) -> Vector3<f64> {
+    fn normal_at(&self, _p: Vector3<Float>) -> Vector3<Float> {
         self.normal()
     }
 
+    /// Returns the texture coordinates of this triangle at `p`, interpolated
+    /// from those of its vertices.
+    ///
+    /// `p` must be a point on this triangle.
+    fn uv_at(&self, p: Vector3<Float>) -> Vector2<Float> {
+        let [uv_a, uv_b, uv_c] = self.vertex_uvs();
+        let (v, w) = self.barycentric(p).unwrap_or((0.0, 0.0));
+        uv_a + v * (uv_b - uv_a) + w * (uv_c - uv_a)
+    }
+
+    /// Returns a unit vector tangent to this triangle, in the direction in
+    /// which its first texture coordinate increases.
+    ///
+    /// If the texture coordinates of the vertices are degenerate, it returns
+    /// an arbitrary unit vector tangent to this triangle.
+    fn tangent_at(&self, _p: Vector3<Float>) -> Vector3<Float> {
+        let [uv_a, uv_b, uv_c] = self.vertex_uvs();
+        let (e1, e2) = (self.b - self.a, self.c - self.a);
+        let (d1, d2) = (uv_b - uv_a, uv_c - uv_a);
+        // the determinant of the matrix mapping edges to texture coordinates
+        let det = d1.x * d2.y - d2.x * d1.y;
+        let t = (e1 * d2.y - e2 * d1.y) / det;
+        if det != 0.0 && t.norm_squared() > 0.0 {
+            // remove any component along the normal left by skewed coordinates
+            let n = self.normal().normalize();
+            (t - t.dot(&n) * n).normalize()
+        } else {
+            perpendicular_to(self.normal())
+        }
+    }
+
+    /// Returns the box bounding this triangle.
+    fn bounds(&self) -> Option<Aabb> {
+        Aabb::from_points([self.a, self.b, self.c])
+    }
+
+    /// Returns an error if the vertices of this triangle are collinear, one of
+    /// its colors is negative or any of its quantities is not finite.
+    fn validate(&self) -> Result<(), Error> {
+        let vertices = [self.a, self.b, self.c];
+        if !vertices.iter().flatten().all(|x| x.is_finite()) {
+            return Err(Error::NotFinite("triangle vertex"))
+        }
+        let (e1, e2) = (self.b - self.a, self.c - self.a);
+        if self.normal().norm() <= MIN_SIN * e1.norm() * e2.norm() {
+            return Err(Error::CollinearVertices)
+        }
+        self.ambient_color.validate("triangle ambient color")?;
+        self.diffuse_color.validate("triangle diffuse color")?;
+        self.specular_color.validate("triangle specular color")?;
+        if !self.shininess.is_finite() {
+            return Err(Error::NotFinite("triangle shininess"))
+        }
+        let uvs = self.vertex_uvs();
+        if !uvs.iter().flatten().all(|x| x.is_finite()) {
+            return Err(Error::NotFinite("triangle texture coordinates"))
+        }
+        Ok(())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ::nalgebra::Vector2;
+    use ::nalgebra::Vector3;
+    use crate::Color;
+    use crate::Error;
+    use crate::Float;
+    use super::Triangle;
+
+    /// Returns the result of creating a white triangle with `vertices`.
+    fn triangle(vertices: [Vector3<Float>; 3]) -> Result<Triangle, Error> {
+        let white = Color::WHITE;
+        Triangle::new(vertices, white, white, white, 2.0, None)
+    }
+
+    #[test]
+    fn valid() {
+        let (a, b, c) = (Vector3::zeros(), Vector3::x(), Vector3::y());
+        assert!(triangle([a, b, c]).is_ok());
+        // a long, thin triangle is still a triangle
+        assert!(triangle([a, 10.0 * b, 10.0 * b + 0.5 * c]).is_ok());
+    }
+
+    #[test]
+    fn collinear() {
+        let (a, b) = (Vector3::zeros(), Vector3::new(1.0, 2.0, 3.0));
+        let error = Some(Error::CollinearVertices);
+        assert_eq!(triangle([a, b, 2.0 * b]).err(), error);
+        assert_eq!(triangle([a, a, b]).err(), error);
+    }
+
+    #[test]
+    fn not_finite() {
+        let a = Vector3::new(Float::NAN, 0.0, 0.0);
+        assert_eq!(
+            triangle([a, Vector3::x(), Vector3::y()]).err(),
+            Some(Error::NotFinite("triangle vertex"))
+        );
+        let uvs = [Vector2::zeros(), Vector2::x(), Vector2::repeat(Float::NAN)];
+        let white = Color::WHITE;
+        let result = Triangle::new(
+            [Vector3::zeros(), Vector3::x(), Vector3::y()],
+            white,
+            white,
+            white,
+            2.0,
+            Some(uvs)
+        );
+        assert_eq!(
+            result.err(),
+            Some(Error::NotFinite("triangle texture coordinates"))
+        );
+    }
+
 }
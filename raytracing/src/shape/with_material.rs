@@ -0,0 +1,104 @@
+use ::nalgebra::Isometry3;
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Error;
+use crate::Float;
+use crate::material::Material;
+use crate::shape::Aabb;
+use crate::shape::Shape;
+
+/// A shape to which a material is assigned, in place of that of the
+/// underlying shape.
+pub struct WithMaterial<S, M> {
+
+    /// The underlying shape, which determines the geometry and the colors of
+    /// this shape.
+    pub shape: S,
+
+    /// The material of this shape.
+    pub material: M
+
+}
+
+impl<S, M> Shape for WithMaterial<S, M>
+    where S: Shape,
+          M: Material
+{
+
+    /// Returns the color of the underlying shape at `p` in ambient white
+    /// light.
+    fn ambient_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.ambient_color_at(p)
+    }
+
+    /// Returns the color of the diffuse reflection of white light off of the
+    /// underlying shape at `p`.
+    fn diffuse_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.diffuse_color_at(p)
+    }
+
+    /// Returns the color of the specular reflection of white light off of the
+    /// underlying shape at `p`.
+    fn specular_color_at(&self, p: Vector3<Float>) -> Color {
+        self.shape.specular_color_at(p)
+    }
+
+    /// Returns the shininess of the underlying shape at `p`.
+    fn shininess_at(&self, p: Vector3<Float>) -> Float {
+        self.shape.shininess_at(p)
+    }
+
+    /// Returns the point at which a ray originating from `l0` in the direction
+    /// of `l` intersects the underlying shape.
+    fn intersect_ray(
+        &self,
+        l0: Vector3<Float>,
+        l: Vector3<Float>
+    ) -> Option<Vector3<Float>> {
+        self.shape.intersect_ray(l0, l)
+    }
+
+    /// Returns a vector normal to the underlying shape at `p`.
+    fn normal_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.shape.normal_at(p)
+    }
+
+    /// Returns the texture coordinates of the underlying shape at `p`.
+    fn uv_at(&self, p: Vector3<Float>) -> Vector2<Float> {
+        self.shape.uv_at(p)
+    }
+
+    /// Returns a unit vector tangent to the underlying shape at `p`.
+    fn tangent_at(&self, p: Vector3<Float>) -> Vector3<Float> {
+        self.shape.tangent_at(p)
+    }
+
+    /// Returns the material of this shape, regardless of `p`.
+    fn material_at(&self, _p: Vector3<Float>) -> Option<&dyn Material> {
+        Some(&self.material)
+    }
+
+    /// Returns a box bounding the underlying shape.
+    fn bounds(&self) -> Option<Aabb> {
+        self.shape.bounds()
+    }
+
+    /// Returns a box bounding the underlying shape at every time between
+    /// `open` and `close`.
+    fn bounds_over(&self, open: Float, close: Float) -> Option<Aabb> {
+        self.shape.bounds_over(open, close)
+    }
+
+    /// Returns the transformation of the underlying shape from rest at
+    /// `time`.
+    fn motion_at(&self, time: Float) -> Option<Isometry3<Float>> {
+        self.shape.motion_at(time)
+    }
+
+    /// Returns an error if the underlying shape is invalid.
+    fn validate(&self) -> Result<(), Error> {
+        self.shape.validate()
+    }
+
+}
@@ -0,0 +1,37 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::texture::Texture;
+
+/// A three-dimensional checkerboard, alternating between two textures in
+/// cubes of unit side length.
+///
+/// Scale it with `Transformed` to change the size of its cubes.
+pub struct Checker<E, O> {
+
+    /// The texture filling the cubes whose integer coordinates sum to an even
+    /// number, including the cube whose corner is the origin.
+    pub even: E,
+
+    /// The texture filling the cubes whose integer coordinates sum to an odd
+    /// number.
+    pub odd: O
+
+}
+
+impl<T, E, O> Texture<T> for Checker<E, O>
+    where E: Texture<T>,
+          O: Texture<T>
+{
+
+    /// Returns the value at `p` of whichever texture fills the cube
+    /// containing `p`.
+    fn value_at(&self, p: Vector3<Float>) -> T {
+        let sum = p.x.floor() + p.y.floor() + p.z.floor();
+        if sum.rem_euclid(2.0) == 0.0 {
+            self.even.value_at(p)
+        } else {
+            self.odd.value_at(p)
+        }
+    }
+
+}
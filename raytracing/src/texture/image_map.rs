@@ -0,0 +1,82 @@
+use ::nalgebra::Vector2;
+use ::std::ops::Add;
+use ::std::ops::Mul;
+use crate::Float;
+use crate::texture::UvTexture;
+
+/// A grid of texels which is wrapped around the surface of a shape by its
+/// texture coordinates, such as a height map or a normal map.
+///
+/// The texture repeats outside of the unit square of texture coordinates,
+/// and its texels are bilinearly interpolated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageMap<T> {
+
+    /// The width of this map, in texels.
+    width: usize,
+
+    /// The height of this map, in texels.
+    height: usize,
+
+    /// The texels of this map, row by row, starting from the row at which the
+    /// second texture coordinate is 0.0.
+    texels: Vec<T>
+
+}
+
+impl<T> ImageMap<T> {
+
+    /// Creates a map of width `width` and height `height` from `texels`, which
+    /// are given row by row, starting from the row at which the second texture
+    /// coordinate is 0.0.
+    ///
+    /// Returns `None` if either dimension is zero or if the number of texels
+    /// is not `width * height`.
+    pub fn new(width: usize, height: usize, texels: Vec<T>) -> Option<Self> {
+        if width == 0 || height == 0 || texels.len() != width * height {
+            return None
+        }
+        Some(ImageMap { width, height, texels })
+    }
+
+    /// Returns the width of this map, in texels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of this map, in texels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the texel in column `x` and row `y` of this map, wrapping
+    /// around its edges.
+    pub fn texel(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self.texels[y * self.width + x]
+    }
+
+}
+
+impl<T> UvTexture<T> for ImageMap<T>
+    where T: Copy + Send + Sync + Add<Output = T> + Mul<Float, Output = T>
+{
+
+    /// Returns the bilinear interpolation of the texels of this map
+    /// surrounding `uv`.
+    fn value_at_uv(&self, uv: Vector2<Float>) -> T {
+        // the position of uv in texels, relative to the centers of the texels
+        let x = uv.x * self.width as Float - 0.5;
+        let y = uv.y * self.height as Float - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+        let top = *self.texel(x0, y0) * (1.0 - fx)
+            + *self.texel(x0 + 1, y0) * fx;
+        let bottom = *self.texel(x0, y0 + 1) * (1.0 - fx)
+            + *self.texel(x0 + 1, y0 + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+}
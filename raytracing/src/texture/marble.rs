@@ -0,0 +1,38 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::texture::Perlin;
+use crate::texture::Texture;
+
+/// Veined marble, made of sinusoidal bands along the x-axis which are
+/// distorted by turbulence.
+///
+/// Its value lies between 0.0 and 1.0; map it to colors with `Ramp`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marble {
+
+    /// The generator of the turbulence distorting this marble.
+    pub perlin: Perlin,
+
+    /// The number of bands of this marble per unit length.
+    pub frequency: Float,
+
+    /// The strength of the turbulence distorting this marble's bands.
+    pub distortion: Float,
+
+    /// The number of octaves of the turbulence distorting this marble's
+    /// bands.
+    pub octaves: u32
+
+}
+
+impl Texture<Float> for Marble {
+
+    /// Returns the value of this marble at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> Float {
+        let turbulence = self.perlin.turbulence(p, self.octaves);
+        let phase = crate::consts::TAU * self.frequency * p.x
+            + self.distortion * turbulence;
+        0.5 + 0.5 * phase.sin()
+    }
+
+}
@@ -0,0 +1,23 @@
+//! Contains `Texture`, a trait implemented by values which vary over space, as
+//! well as various procedural textures, image maps and combinators that
+//! implement `Texture`.
+
+mod checker;
+mod image_map;
+mod marble;
+mod noise;
+mod ramp;
+mod texture;
+mod transformed;
+mod wood;
+mod worley;
+
+pub use checker::*;
+pub use image_map::*;
+pub use marble::*;
+pub use noise::*;
+pub use ramp::*;
+pub use texture::*;
+pub use transformed::*;
+pub use wood::*;
+pub use worley::*;
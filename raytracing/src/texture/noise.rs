@@ -0,0 +1,193 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::texture::Texture;
+
+/// A generator of Perlin gradient noise, as described in Ken Perlin's
+/// "Improving Noise" (2002).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Perlin {
+
+    /// A permutation of `0..256`, repeated twice so that it may be indexed
+    /// without wrapping.
+    perm: Vec<u8>
+
+}
+
+impl Perlin {
+
+    /// Creates a noise generator whose permutation is determined by `seed`.
+    pub fn new(seed: u64) -> Self {
+        let mut perm: Vec<u8> = (0..=255).collect();
+        // shuffle the identity permutation with Fisher-Yates
+        let mut state = seed;
+        for i in (1..perm.len()).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            perm.swap(i, j);
+        }
+        perm.extend_from_within(..);
+        Perlin { perm }
+    }
+
+    /// Returns the value of the noise at `p`, which lies between -1.0 and 1.0.
+    pub fn noise(&self, p: Vector3<Float>) -> Float {
+        // the cell containing p, and the position of p within it
+        let (xi, yi, zi) = (
+            p.x.floor().rem_euclid(256.0) as usize,
+            p.y.floor().rem_euclid(256.0) as usize,
+            p.z.floor().rem_euclid(256.0) as usize
+        );
+        let (x, y, z) = (
+            p.x - p.x.floor(),
+            p.y - p.y.floor(),
+            p.z - p.z.floor()
+        );
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        // hash the coordinates of the cell's eight corners
+        let perm = &self.perm;
+        let a = perm[xi] as usize + yi;
+        let aa = perm[a] as usize + zi;
+        let ab = perm[a + 1] as usize + zi;
+        let b = perm[xi + 1] as usize + yi;
+        let ba = perm[b] as usize + zi;
+        let bb = perm[b + 1] as usize + zi;
+        // blend the contributions of the corners' gradients
+        lerp(w,
+            lerp(v,
+                lerp(u,
+                    grad(perm[aa], x, y, z),
+                    grad(perm[ba], x - 1.0, y, z)),
+                lerp(u,
+                    grad(perm[ab], x, y - 1.0, z),
+                    grad(perm[bb], x - 1.0, y - 1.0, z))),
+            lerp(v,
+                lerp(u,
+                    grad(perm[aa + 1], x, y, z - 1.0),
+                    grad(perm[ba + 1], x - 1.0, y, z - 1.0)),
+                lerp(u,
+                    grad(perm[ab + 1], x, y - 1.0, z - 1.0),
+                    grad(perm[bb + 1], x - 1.0, y - 1.0, z - 1.0))))
+    }
+
+    /// Returns the fractal sum of `octaves` octaves of noise at `p`, each
+    /// octave having `lacunarity` times the frequency and `gain` times the
+    /// amplitude of the last.
+    ///
+    /// The result is normalized to lie between -1.0 and 1.0.
+    pub fn fbm(
+        &self,
+        p: Vector3<Float>,
+        octaves: u32,
+        lacunarity: Float,
+        gain: Float
+    ) -> Float {
+        let (mut sum, mut total) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        for _ in 0..octaves {
+            sum += amplitude * self.noise(p * frequency);
+            total += amplitude;
+            frequency *= lacunarity;
+            amplitude *= gain;
+        }
+        if total > 0.0 { sum / total } else { 0.0 }
+    }
+
+    /// Returns the fractal sum of the absolute values of `octaves` octaves of
+    /// noise at `p`, each octave having twice the frequency and half the
+    /// amplitude of the last.
+    ///
+    /// The result is normalized to lie between 0.0 and 1.0.
+    pub fn turbulence(&self, p: Vector3<Float>, octaves: u32) -> Float {
+        let (mut sum, mut total) = (0.0, 0.0);
+        let mut amplitude = 1.0;
+        for octave in 0..octaves {
+            let frequency = (1 << octave) as Float;
+            sum += amplitude * self.noise(p * frequency).abs();
+            total += amplitude;
+            amplitude *= 0.5;
+        }
+        if total > 0.0 { sum / total } else { 0.0 }
+    }
+
+}
+
+/// Fractal Perlin noise, remapped to lie between 0.0 and 1.0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise {
+
+    /// The generator of this noise.
+    pub perlin: Perlin,
+
+    /// The number of octaves summed by this noise.
+    ///
+    /// A value of 1 yields plain Perlin noise.
+    pub octaves: u32,
+
+    /// The factor by which the frequency increases from one octave to the
+    /// next, usually 2.0.
+    pub lacunarity: Float,
+
+    /// The factor by which the amplitude decreases from one octave to the
+    /// next, usually 0.5.
+    pub gain: Float
+
+}
+
+impl Texture<Float> for Noise {
+
+    /// Returns the value of this noise at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> Float {
+        let n = self.perlin.fbm(p, self.octaves, self.lacunarity, self.gain);
+        0.5 + 0.5 * n
+    }
+
+}
+
+/// Fractal turbulence, the sum of the absolute values of octaves of Perlin
+/// noise, which lies between 0.0 and 1.0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Turbulence {
+
+    /// The generator of this turbulence.
+    pub perlin: Perlin,
+
+    /// The number of octaves summed by this turbulence.
+    pub octaves: u32
+
+}
+
+impl Texture<Float> for Turbulence {
+
+    /// Returns the value of this turbulence at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> Float {
+        self.perlin.turbulence(p, self.octaves)
+    }
+
+}
+
+/// Advances `state` and returns the next output of the SplitMix64 generator.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Returns the value of Perlin's quintic fade curve at `t`.
+fn fade(t: Float) -> Float {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Linearly interpolates between `a` and `b` by `t`.
+fn lerp(t: Float, a: Float, b: Float) -> Float {
+    a + t * (b - a)
+}
+
+/// Returns the dot product of the offset `(x, y, z)` with the gradient
+/// selected by the low four bits of `hash`.
+fn grad(hash: u8, x: Float, y: Float, z: Float) -> Float {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
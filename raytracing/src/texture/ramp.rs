@@ -0,0 +1,77 @@
+use ::nalgebra::Vector3;
+use ::std::ops::Add;
+use ::std::ops::Mul;
+use crate::Float;
+use crate::texture::Texture;
+
+/// A texture which maps the values of a scalar texture to values of type `T`
+/// by linearly interpolating between stops, such as a color gradient over a
+/// `Marble` or a range of shininesses over a `Noise`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ramp<X, T> {
+
+    /// The scalar texture whose values are mapped by this ramp.
+    pub input: X,
+
+    /// The stops of this ramp, each a scalar paired with the value it maps
+    /// to, in increasing order of their scalars.
+    ///
+    /// Scalars below the first stop or above the last are mapped to the value
+    /// of that stop.
+    stops: Vec<(Float, T)>
+
+}
+
+impl<X, T> Ramp<X, T> {
+
+    /// Creates a ramp mapping the values of `input` by `stops`, each a scalar
+    /// paired with the value it maps to.
+    ///
+    /// Returns `None` if there are no stops, or their scalars are not finite
+    /// or not in increasing order.
+    pub fn new(input: X, stops: Vec<(Float, T)>) -> Option<Self> {
+        let finite = stops.iter().all(|(t, _)| t.is_finite());
+        let sorted = stops.windows(2).all(|w| w[0].0 <= w[1].0);
+        if stops.is_empty() || !finite || !sorted {
+            return None
+        }
+        Some(Ramp { input, stops })
+    }
+
+    /// Creates a ramp mapping the values of `input` from 0.0 to 1.0 onto the
+    /// values from `lo` to `hi`.
+    pub fn linear(input: X, lo: T, hi: T) -> Self {
+        Ramp { input, stops: vec![(0.0, lo), (1.0, hi)] }
+    }
+
+    /// Returns the stops of this ramp, in increasing order of their scalars.
+    pub fn stops(&self) -> &[(Float, T)] {
+        &self.stops
+    }
+
+}
+
+impl<X, T> Texture<T> for Ramp<X, T>
+    where X: Texture<Float>,
+          T: Copy + Send + Sync + Add<Output = T> + Mul<Float, Output = T>
+{
+
+    /// Returns the value which this ramp maps the value of its input at `p`
+    /// to.
+    fn value_at(&self, p: Vector3<Float>) -> T {
+        let s = self.input.value_at(p);
+        // the index of the first stop after s
+        let i = self.stops.partition_point(|&(t, _)| t <= s);
+        if i == 0 {
+            self.stops[0].1
+        } else if i == self.stops.len() {
+            self.stops[i - 1].1
+        } else {
+            let (t0, v0) = self.stops[i - 1];
+            let (t1, v1) = self.stops[i];
+            let k = (s - t0) / (t1 - t0);
+            v0 * (1.0 - k) + v1 * k
+        }
+    }
+
+}
@@ -0,0 +1,61 @@
+use ::nalgebra::Vector2;
+use ::nalgebra::Vector3;
+use crate::Color;
+use crate::Float;
+
+/// Implemented by values of type `T` which vary over space, such as the color
+/// or the shininess of a shape.
+pub trait Texture<T>: Send + Sync {
+
+    /// Returns the value of the implementing texture at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> T;
+
+}
+
+/// Implemented by values of type `T` which vary over the surface of a shape,
+/// as parameterized by its texture coordinates, such as image maps.
+pub trait UvTexture<T>: Send + Sync {
+
+    /// Returns the value of the implementing texture at the texture
+    /// coordinates `uv`.
+    fn value_at_uv(&self, uv: Vector2<Float>) -> T;
+
+}
+
+/// A color is a texture which takes on that color everywhere.
+impl Texture<Color> for Color {
+
+    /// Returns this color, regardless of `p`.
+    fn value_at(&self, _p: Vector3<Float>) -> Color {
+        *self
+    }
+
+}
+
+/// A number is a texture which takes on that number everywhere.
+impl Texture<Float> for Float {
+
+    /// Returns this number, regardless of `p`.
+    fn value_at(&self, _p: Vector3<Float>) -> Float {
+        *self
+    }
+
+}
+
+impl<T, X> Texture<T> for Box<X> where X: Texture<T> + ?Sized {
+
+    /// Returns the value of the boxed texture at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> T {
+        self.as_ref().value_at(p)
+    }
+
+}
+
+impl<T, X> UvTexture<T> for Box<X> where X: UvTexture<T> + ?Sized {
+
+    /// Returns the value of the boxed texture at `uv`.
+    fn value_at_uv(&self, uv: Vector2<Float>) -> T {
+        self.as_ref().value_at_uv(uv)
+    }
+
+}
@@ -0,0 +1,59 @@
+use ::nalgebra::Affine3;
+use ::nalgebra::Matrix4;
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::texture::Texture;
+
+/// A texture which is moved, rotated, scaled or otherwise transformed by an
+/// affine transformation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transformed<X> {
+
+    /// The texture which is transformed.
+    pub texture: X,
+
+    /// The inverse of the transformation applied to the texture, which maps
+    /// points in space back onto the untransformed texture.
+    inverse: Affine3<Float>
+
+}
+
+impl<X> Transformed<X> {
+
+    /// Creates a texture which is `texture` transformed by `transform`.
+    ///
+    /// Returns `None` if `transform` is not invertible.
+    pub fn new(texture: X, transform: Affine3<Float>) -> Option<Self> {
+        let inverse = transform.try_inverse()?;
+        Some(Transformed { texture, inverse })
+    }
+
+    /// Creates a texture which is `texture` scaled by `scale` along each axis
+    /// and then moved by `offset`.
+    ///
+    /// Returns `None` if any component of `scale` is zero.
+    pub fn scaled(
+        texture: X,
+        scale: Vector3<Float>,
+        offset: Vector3<Float>
+    ) -> Option<Self> {
+        let transform = Matrix4::new_translation(&offset)
+            * Matrix4::new_nonuniform_scaling(&scale);
+        Transformed::new(texture, Affine3::from_matrix_unchecked(transform))
+    }
+
+    /// Returns the transformation applied to the texture.
+    pub fn transform(&self) -> Affine3<Float> {
+        self.inverse.inverse()
+    }
+
+}
+
+impl<T, X> Texture<T> for Transformed<X> where X: Texture<T> {
+
+    /// Returns the value of this texture at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> T {
+        self.texture.value_at(self.inverse.transform_point(&p.into()).coords)
+    }
+
+}
@@ -0,0 +1,39 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::texture::Perlin;
+use crate::texture::Texture;
+
+/// Wood grain, made of concentric growth rings around the y-axis which are
+/// perturbed by noise.
+///
+/// Its value lies between 0.0 and 1.0, rising steadily across each ring; map
+/// it to colors with `Ramp`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wood {
+
+    /// The generator of the noise perturbing this wood's rings.
+    pub perlin: Perlin,
+
+    /// The number of rings of this wood per unit length.
+    pub frequency: Float,
+
+    /// The strength of the noise perturbing this wood's rings, in rings.
+    pub distortion: Float,
+
+    /// The number of octaves of the noise perturbing this wood's rings.
+    pub octaves: u32
+
+}
+
+impl Texture<Float> for Wood {
+
+    /// Returns the value of this wood at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> Float {
+        // the distance from p to the axis of the trunk
+        let r = (p.x * p.x + p.z * p.z).sqrt();
+        let n = self.perlin.fbm(p, self.octaves, 2.0, 0.5);
+        let rings = self.frequency * r + self.distortion * n;
+        rings - rings.floor()
+    }
+
+}
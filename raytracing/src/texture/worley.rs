@@ -0,0 +1,93 @@
+use ::nalgebra::Vector3;
+use crate::Float;
+use crate::texture::Texture;
+use crate::texture::splitmix64;
+
+/// The quantity computed by a `Worley` texture from the distances to its
+/// nearest feature points.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WorleyFeature {
+
+    /// The distance to the nearest feature point, which yields rounded cells.
+    F1,
+
+    /// The distance to the second-nearest feature point.
+    F2,
+
+    /// The difference between the distances to the second-nearest and the
+    /// nearest feature points, which yields a network of cracks along the
+    /// borders between cells.
+    F2MinusF1
+
+}
+
+/// Cellular noise as described by Steven Worley, built from one randomly
+/// placed feature point in each cube of unit side length.
+///
+/// Its value usually lies between 0.0 and 1.0.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Worley {
+
+    /// The seed which determines the placement of the feature points.
+    pub seed: u64,
+
+    /// The quantity computed by this texture.
+    pub feature: WorleyFeature
+
+}
+
+impl Worley {
+
+    /// Returns the feature point of the cube whose corner nearest the origin
+    /// is `(i, j, k)`.
+    fn feature_point(&self, i: i64, j: i64, k: i64) -> Vector3<Float> {
+        let mut state = self.seed
+            ^ (i as u64).wrapping_mul(0x8da6b343)
+            ^ (j as u64).wrapping_mul(0xd8163841)
+            ^ (k as u64).wrapping_mul(0xcb1ab31f);
+        let mut next = || {
+            (splitmix64(&mut state) >> 11) as Float / (1u64 << 53) as Float
+        };
+        Vector3::new(
+            i as Float + next(),
+            j as Float + next(),
+            k as Float + next()
+        )
+    }
+
+}
+
+impl Texture<Float> for Worley {
+
+    /// Returns the value of this texture at `p`.
+    fn value_at(&self, p: Vector3<Float>) -> Float {
+        let (i, j, k) = (
+            p.x.floor() as i64,
+            p.y.floor() as i64,
+            p.z.floor() as i64
+        );
+        // the distances to the nearest and second-nearest feature points
+        let (mut f1, mut f2) = (Float::INFINITY, Float::INFINITY);
+        // only the 27 cubes surrounding p can contain the nearest points
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                for dk in -1..=1 {
+                    let q = self.feature_point(i + di, j + dj, k + dk);
+                    let d = (q - p).norm();
+                    if d < f1 {
+                        f2 = f1;
+                        f1 = d;
+                    } else if d < f2 {
+                        f2 = d;
+                    }
+                }
+            }
+        }
+        match self.feature {
+            WorleyFeature::F1 => f1,
+            WorleyFeature::F2 => f2,
+            WorleyFeature::F2MinusF1 => f2 - f1
+        }
+    }
+
+}
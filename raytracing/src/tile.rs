@@ -0,0 +1,556 @@
+use ::nalgebra::Vector3;
+use ::rand::Rng;
+use ::rand::rngs::ThreadRng;
+use ::rayon::iter::IntoParallelIterator;
+use ::rayon::iter::ParallelIterator;
+use ::std::sync::Mutex;
+use ::std::sync::atomic::AtomicBool;
+use ::std::sync::atomic::AtomicUsize;
+use ::std::sync::atomic::Ordering;
+use ::std::time::Duration;
+use ::std::time::Instant;
+use crate::Color;
+use crate::Float;
+use crate::image::Image;
+use crate::render::Renderer;
+use crate::sample;
+
+/// The order in which a `TileScheduler` starts rendering the tiles of an
+/// image.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TileOrder {
+
+    /// Row by row from the top, left to right.
+    Scanline,
+
+    /// Outwards from the center in rings, so that the middle of the image,
+    /// where the subject usually is, appears first.
+    Spiral,
+
+    /// Along a Hilbert curve, which keeps consecutive tiles adjacent to each
+    /// other.
+    Hilbert
+
+}
+
+/// A rectangular region of an image.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Region {
+
+    /// The column of the left edge of this region.
+    pub x: u32,
+
+    /// The row of the top edge of this region.
+    pub y: u32,
+
+    /// The width of this region, in pixels.
+    pub width: u32,
+
+    /// The height of this region, in pixels.
+    pub height: u32
+
+}
+
+/// A region of an image which a `TileScheduler` renders as a unit.
+pub type Tile = Region;
+
+impl Region {
+
+    /// Creates the region covering the whole of an image of width `width` and
+    /// height `height`.
+    pub fn full(width: u32, height: u32) -> Self {
+        Region { x: 0, y: 0, width, height }
+    }
+
+    /// Returns the part of this region which lies within an image of width
+    /// `width` and height `height`, or `None` if there is none.
+    pub fn clamped(&self, width: u32, height: u32) -> Option<Region> {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        let region = Region {
+            x,
+            y,
+            width: self.width.min(width - x),
+            height: self.height.min(height - y)
+        };
+        (region.area() > 0).then_some(region)
+    }
+
+    /// Returns the number of pixels in this region.
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// Returns the columns and rows of the pixels in this region, row by row
+    /// from the top.
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32)> {
+        let Region { x, y, width, height } = *self;
+        (y..y + height).flat_map(move |py| (x..x + width).map(move |px| {
+            (px, py)
+        }))
+    }
+
+}
+
+/// A snapshot of the progress of a render by a `TileScheduler`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Progress {
+
+    /// The number of tiles rendered so far.
+    pub tiles_done: usize,
+
+    /// The number of tiles in the image.
+    pub tiles_total: usize,
+
+    /// The number of primary rays cast so far, which is one per pixel of
+    /// every tile rendered.
+    pub rays: u64,
+
+    /// The time since the render started.
+    pub elapsed: Duration
+
+}
+
+impl Progress {
+
+    /// Returns the fraction of the tiles which have been rendered, between
+    /// 0.0 and 1.0.
+    pub fn fraction(&self) -> f64 {
+        if self.tiles_total == 0 {
+            return 1.0
+        }
+        self.tiles_done as f64 / self.tiles_total as f64
+    }
+
+    /// Returns whether every tile has been rendered, which is not the case if
+    /// the render was cancelled.
+    pub fn is_complete(&self) -> bool {
+        self.tiles_done == self.tiles_total
+    }
+
+    /// Returns the estimated time until every tile has been rendered, assuming
+    /// the remaining tiles take as long as those rendered so far, or `None`
+    /// if no tile has been rendered yet.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.tiles_done == 0 {
+            return None
+        }
+        let remaining = (self.tiles_total - self.tiles_done) as f64;
+        Some(self.elapsed.mul_f64(remaining / self.tiles_done as f64))
+    }
+
+    /// Returns the number of primary rays cast per second so far.
+    pub fn rays_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.rays as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+}
+
+/// A scheduler which splits an image into square tiles and renders them in
+/// parallel, each on a single thread so that the rays of neighboring pixels
+/// share caches.
+///
+/// Renders report their progress after every tile, and may be cancelled
+/// between tiles, leaving the tiles rendered so far in place. They may also be
+/// restricted to a region of the image, with rays generated as for the whole
+/// image.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TileScheduler {
+
+    /// The width and height of the tiles, in pixels, which must be at least 1.
+    /// Tiles on the right and bottom edges of an image may be smaller.
+    pub tile_size: u32,
+
+    /// The order in which the tiles start rendering.
+    pub order: TileOrder,
+
+    /// The region of the image to render, or `None` to render all of it.
+    pub region: Option<Region>
+
+}
+
+impl Default for TileScheduler {
+
+    /// Returns the scheduler of tiles of 32 by 32 pixels in spiral order over
+    /// the whole image.
+    fn default() -> Self {
+        TileScheduler {
+            tile_size: 32,
+            order: TileOrder::Spiral,
+            region: None
+        }
+    }
+
+}
+
+impl TileScheduler {
+
+    /// Returns the region of an image of width `width` and height `height`
+    /// which this scheduler renders, or `None` if it renders nothing.
+    pub fn region_in(&self, width: u32, height: u32) -> Option<Region> {
+        self.region
+            .unwrap_or_else(|| Region::full(width, height))
+            .clamped(width, height)
+    }
+
+    /// Returns the tiles covering the region of an image of width `width` and
+    /// height `height` which this scheduler renders, in the order in which
+    /// they start rendering.
+    pub fn tiles(&self, width: u32, height: u32) -> Vec<Tile> {
+        let Some(region) = self.region_in(width, height) else {
+            return Vec::new()
+        };
+        let size = self.tile_size.max(1);
+        let cols = region.width.div_ceil(size);
+        let rows = region.height.div_ceil(size);
+        let mut cells: Vec<(u32, u32)> = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (col, row)))
+            .collect();
+        match self.order {
+            TileOrder::Scanline => {},
+            TileOrder::Spiral => {
+                let center_col = (cols as Float - 1.0) / 2.0;
+                let center_row = (rows as Float - 1.0) / 2.0;
+                // the ring around the center and the angle within it
+                let key = |&(col, row): &(u32, u32)| {
+                    let dx = col as Float - center_col;
+                    let dy = row as Float - center_row;
+                    (dx.abs().max(dy.abs()), dy.atan2(dx))
+                };
+                cells.sort_by(|a, b| {
+                    let (ring_a, angle_a) = key(a);
+                    let (ring_b, angle_b) = key(b);
+                    ring_a.total_cmp(&ring_b)
+                        .then(angle_a.total_cmp(&angle_b))
+                });
+            },
+            TileOrder::Hilbert => {
+                let n = cols.max(rows).next_power_of_two();
+                cells.sort_by_key(|&(col, row)| hilbert_index(n, col, row));
+            }
+        }
+        cells.into_iter()
+            .map(|(col, row)| {
+                let (x, y) = (col * size, row * size);
+                Tile {
+                    x: region.x + x,
+                    y: region.y + y,
+                    width: size.min(region.width - x),
+                    height: size.min(region.height - y)
+                }
+            })
+            .collect()
+    }
+
+    /// Uses the algorithm `renderer` to rasterize the scene it references into
+    /// the buffer `buf`, the width and height of which are `width` and
+    /// `height` respectively, tile by tile, leaving the pixels outside of the
+    /// region rendered unchanged.
+    ///
+    /// Each pixel is the color along the ray through its center, the center
+    /// of the camera's lens, at the time its shutter opens, or black if there
+    /// is no such ray. `progress` is called after each tile is rendered, and
+    /// setting `cancel` stops the render before the next tile. Returns the
+    /// progress of the render when it stopped.
+    pub fn rasterize_into<R, C, P>(
+        &self,
+        renderer: &R,
+        buf: &mut [C],
+        width: u32,
+        height: u32,
+        progress: P,
+        cancel: &AtomicBool
+    ) -> Result<Progress, R::CastError>
+        where R: Renderer + Sync,
+              C: From<Color> + Send,
+              P: FnMut(&Progress) + Send
+    {
+        self.run(
+            width,
+            height,
+            |_, x, y| {
+                let (x, y) = (x as Float + 0.5, y as Float + 0.5);
+                sample(renderer, x, y, width, height, Vector3::zeros())
+            },
+            |tile, colors| {
+                for ((x, y), color) in tile.pixels().zip(colors) {
+                    let i = y as usize * width as usize + x as usize;
+                    buf[i] = C::from(color);
+                }
+            },
+            progress,
+            cancel
+        )
+    }
+
+    /// Uses the algorithm `renderer` to rasterize the region of the scene it
+    /// references which this scheduler renders into the buffer `buf`, which
+    /// holds only that region of an image of width `width` and height
+    /// `height`, tile by tile.
+    ///
+    /// `buf` holds the pixels of the region row by row from the top, and
+    /// nothing is rendered if the region is empty. Otherwise, this is the same
+    /// as `rasterize_into`.
+    pub fn rasterize_cropped_into<R, C, P>(
+        &self,
+        renderer: &R,
+        buf: &mut [C],
+        width: u32,
+        height: u32,
+        progress: P,
+        cancel: &AtomicBool
+    ) -> Result<Progress, R::CastError>
+        where R: Renderer + Sync,
+              C: From<Color> + Send,
+              P: FnMut(&Progress) + Send
+    {
+        let region = self.region_in(width, height)
+            .unwrap_or_else(|| Region::full(0, 0));
+        self.run(
+            width,
+            height,
+            |_, x, y| {
+                let (x, y) = (x as Float + 0.5, y as Float + 0.5);
+                sample(renderer, x, y, width, height, Vector3::zeros())
+            },
+            |tile, colors| {
+                for ((x, y), color) in tile.pixels().zip(colors) {
+                    let (x, y) = (x - region.x, y - region.y);
+                    let i = y as usize * region.width as usize + x as usize;
+                    buf[i] = C::from(color);
+                }
+            },
+            progress,
+            cancel
+        )
+    }
+
+    /// Uses the algorithm `renderer` to render the scene it references tile by
+    /// tile, adding a sample of weight 1.0 to each pixel of `image` along the
+    /// ray through a point chosen at random within that pixel, as well as at
+    /// a point on the camera's lens and a time chosen at random.
+    ///
+    /// `progress` is called after each tile is rendered, and setting `cancel`
+    /// stops the render before the next tile, so that only the pixels of the
+    /// tiles rendered so far gain a sample. The pixels outside of the region
+    /// rendered gain none. Returns the progress of the render
+    /// when it stopped.
+    pub fn render_image<R, P>(
+        &self,
+        renderer: &R,
+        image: &mut Image,
+        progress: P,
+        cancel: &AtomicBool
+    ) -> Result<Progress, R::CastError>
+        where R: Renderer + Sync,
+              P: FnMut(&Progress) + Send
+    {
+        let (width, height) = (image.width(), image.height());
+        self.run(
+            width,
+            height,
+            |rng, x, y| {
+                // a random point within the pixel
+                let x = x as Float + rng.gen::<Float>();
+                let y = y as Float + rng.gen::<Float>();
+                let u = Vector3::from_fn(|_, _| rng.gen());
+                sample(renderer, x, y, width, height, u)
+            },
+            |tile, colors| {
+                for ((x, y), color) in tile.pixels().zip(colors) {
+                    image.add_sample(x, y, color, 1.0);
+                }
+            },
+            progress,
+            cancel
+        )
+    }
+
+    /// Renders the tiles of the region of an image of width `width` and height
+    /// `height` which this scheduler renders in parallel, calling `sample` for
+    /// the color of each pixel, then `write` with each tile and the colors of
+    /// its pixels, row by row from the top, and `progress` with the progress
+    /// so far.
+    ///
+    /// Stops before the next tile once `cancel` is set or `sample` fails, and
+    /// returns the error of the first failure, if any.
+    fn run<F, W, P, E>(
+        &self,
+        width: u32,
+        height: u32,
+        sample: F,
+        write: W,
+        progress: P,
+        cancel: &AtomicBool
+    ) -> Result<Progress, E>
+        where F: Fn(&mut ThreadRng, u32, u32) -> Result<Color, E> + Sync,
+              W: FnMut(&Tile, Vec<Color>) + Send,
+              P: FnMut(&Progress) + Send,
+              E: Send
+    {
+        let tiles = self.tiles(width, height);
+        let start = Instant::now();
+        let mut state = Progress {
+            tiles_done: 0,
+            tiles_total: tiles.len(),
+            rays: 0,
+            elapsed: Duration::ZERO
+        };
+        // the index of the next tile to start rendering
+        let next = AtomicUsize::new(0);
+        // set once a tile fails, to stop the others
+        let failed = AtomicBool::new(false);
+        let shared = Mutex::new((write, progress, &mut state));
+        /* each worker takes the next tile until there are none left, which
+         * starts the tiles in order regardless of how rayon splits work */
+        (0..::rayon::current_num_threads())
+            .into_par_iter()
+            .try_for_each(|_| {
+            let mut rng = ::rand::thread_rng();
+            loop {
+                if cancel.load(Ordering::Relaxed)
+                    || failed.load(Ordering::Relaxed) {
+                    return Ok(())
+                }
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(tile) = tiles.get(i) else {
+                    return Ok(())
+                };
+                let colors = tile.pixels()
+                    .map(|(x, y)| sample(&mut rng, x, y))
+                    .collect::<Result<Vec<_>, _>>()
+                    .inspect_err(|_| failed.store(true, Ordering::Relaxed))?;
+                let mut guard = shared.lock().unwrap();
+                let (write, progress, state) = &mut *guard;
+                write(tile, colors);
+                state.tiles_done += 1;
+                state.rays += tile.area();
+                state.elapsed = start.elapsed();
+                progress(state);
+            }
+        })?;
+        drop(shared);
+        Ok(state)
+    }
+
+}
+
+/// Returns the distance along the Hilbert curve filling a grid of `n` by `n`
+/// cells, where `n` is a power of two, of the cell in column `x` and row `y`.
+fn hilbert_index(n: u32, mut x: u32, mut y: u32) -> u64 {
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u64;
+        let ry = (y & s > 0) as u64;
+        d += s as u64 * s as u64 * ((3 * rx) ^ ry);
+        // rotate the quadrant so that the curve within it is in standard form
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            ::std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Region;
+    use super::TileOrder;
+    use super::TileScheduler;
+
+    const ORDERS: [TileOrder; 3] = [
+        TileOrder::Scanline,
+        TileOrder::Spiral,
+        TileOrder::Hilbert
+    ];
+
+    /// Asserts that the tiles of `scheduler` cover each pixel of its region
+    /// of an image of width `width` and height `height` exactly once.
+    fn assert_covers(scheduler: &TileScheduler, width: u32, height: u32) {
+        let mut counts = vec![0; width as usize * height as usize];
+        for tile in scheduler.tiles(width, height) {
+            for (x, y) in tile.pixels() {
+                assert!(x < width && y < height, "{tile:?} is out of bounds");
+                counts[(y * width + x) as usize] += 1;
+            }
+        }
+        let region = scheduler.region_in(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let inside = region.is_some_and(|r| {
+                    (r.x..r.x + r.width).contains(&x)
+                        && (r.y..r.y + r.height).contains(&y)
+                });
+                let count = counts[(y * width + x) as usize];
+                assert_eq!(count, inside as u32, "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_cover_image_once() {
+        for order in ORDERS {
+            for tile_size in [1, 7, 32, 100] {
+                for (width, height) in [(64, 64), (100, 37), (1, 90), (0, 5)] {
+                    let scheduler =
+                        TileScheduler { tile_size, order, region: None };
+                    assert_covers(&scheduler, width, height);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_cover_region_once() {
+        let regions = [
+            Region { x: 10, y: 5, width: 50, height: 20 },
+            Region { x: 90, y: 30, width: 50, height: 50 },
+            Region { x: 200, y: 0, width: 10, height: 10 }
+        ];
+        for order in ORDERS {
+            for region in regions {
+                let scheduler =
+                    TileScheduler { tile_size: 8, order, region: Some(region) };
+                assert_covers(&scheduler, 100, 40);
+            }
+        }
+    }
+
+    #[test]
+    fn hilbert_tiles_are_adjacent() {
+        let scheduler = TileScheduler {
+            tile_size: 4,
+            order: TileOrder::Hilbert,
+            region: None
+        };
+        let tiles = scheduler.tiles(32, 32);
+        for pair in tiles.windows(2) {
+            let dx = pair[0].x.abs_diff(pair[1].x);
+            let dy = pair[0].y.abs_diff(pair[1].y);
+            assert_eq!(dx + dy, 4, "{:?} and {:?}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn spiral_starts_at_center() {
+        let scheduler = TileScheduler {
+            tile_size: 10,
+            order: TileOrder::Spiral,
+            region: None
+        };
+        let first = scheduler.tiles(50, 30)[0];
+        assert_eq!((first.x, first.y), (20, 10));
+    }
+
+}